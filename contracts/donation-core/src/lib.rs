@@ -0,0 +1,589 @@
+#![no_std]
+//! Types and helpers shared by the donation contracts.
+use soroban_sdk::{
+    contracterror, contractevent, contracttype, panic_with_error, symbol_short, Address, Env,
+    IntoVal, Map, String, Symbol, Val, Vec,
+};
+
+// Storage keys
+pub const CAMPAIGNS: Symbol = symbol_short!("CAMP");
+pub const CAMPAIGN_COUNT: Symbol = symbol_short!("COUNT");
+pub const HISTORY: Symbol = symbol_short!("HISTORY");
+pub const TTL_CONFIG: Symbol = symbol_short!("TTLCFG");
+
+// Default TTL thresholds (in ledgers, ~5 seconds each)
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+pub const DEFAULT_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+pub const DEFAULT_TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+// Basis points in a whole split
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+pub const CATEGORIES: [CampaignCategory; 5] = [
+    CampaignCategory::Zakat,
+    CampaignCategory::Pendidikan,
+    CampaignCategory::Kesehatan,
+    CampaignCategory::BencanaAlam,
+    CampaignCategory::UMKM,
+];
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InvalidAmount = 1,
+    InvalidTarget = 2,
+    Overflow = 3,
+    BelowMinimum = 4,
+    AboveMaximum = 5,
+    InvalidLimits = 6,
+    ExceedsTarget = 7,
+    InvalidRedirect = 8,
+    InvalidShares = 9,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CampaignCategory {
+    Zakat,
+    Pendidikan,
+    Kesehatan,
+    BencanaAlam,
+    UMKM,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CampaignStatus {
+    Draft,
+    PendingReview,
+    Active,
+    Completed,
+    Closed,
+    Rejected,
+}
+
+// How the target is treated: Fixed completes the campaign when reached,
+// Flexible only displays it, OpenEnded has no target at all
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GoalType {
+    Fixed,
+    Flexible,
+    OpenEnded,
+}
+
+// What happens to the part of a donation that goes past the target
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExcessPolicy {
+    AcceptAll,
+    RejectExcess,
+    RefundExcess,
+    RedirectExcess(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Campaign {
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    pub category: CampaignCategory,
+    // Region or location code (e.g. "ID-NB"), empty when not set
+    pub region: String,
+    pub target_amount: i128,
+    pub current_amount: i128,
+    // Total paid out to the recipient so far
+    pub withdrawn_amount: i128,
+    // Funds moved in by the admin (other campaigns, category pools) or out
+    pub redirected_in: i128,
+    pub redirected_out: i128,
+    // Returned to donors after they were accepted
+    pub refunded_amount: i128,
+    pub recipient: Address,
+    pub status: CampaignStatus,
+    pub created_at: u64,
+    // Per-donation bounds, 0 means no bound
+    pub min_donation: i128,
+    pub max_donation: i128,
+    pub excess_policy: ExcessPolicy,
+    pub goal_type: GoalType,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Donation {
+    pub campaign_id: u32,
+    pub donor: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub is_anonymous: bool,
+    // Shared by every leg of a batch or split donation, 0 for single donations
+    pub batch_id: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignRevision {
+    pub version: u32,
+    pub title: String,
+    pub description: String,
+    pub target_amount: i128,
+    pub editor: Address,
+    pub timestamp: u64,
+}
+
+// Result of recomputing a campaign's books from its donation records
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IntegrityReport {
+    pub campaign_id: u32,
+    pub donation_total: i128,
+    pub current_amount: i128,
+    pub withdrawn_amount: i128,
+    pub redirected_in: i128,
+    pub redirected_out: i128,
+    pub refunded_amount: i128,
+    pub is_consistent: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TtlConfig {
+    pub threshold: u32,
+    pub extend_to: u32,
+}
+
+// Events
+#[contractevent(topics = ["update"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignUpdated {
+    #[topic]
+    pub campaign_id: u32,
+    pub version: u32,
+    pub target_amount: i128,
+}
+
+// Status state machine
+impl CampaignStatus {
+    // Whether a campaign in this status can move to `next`
+    pub fn can_transition_to(&self, next: &CampaignStatus) -> bool {
+        use CampaignStatus::*;
+
+        matches!(
+            (self, next),
+            (Draft, PendingReview)
+                | (Rejected, PendingReview)
+                | (PendingReview, Active)
+                | (PendingReview, Rejected)
+                | (Active, Completed)
+                | (
+                    Draft | PendingReview | Rejected | Active | Completed,
+                    Closed
+                )
+        )
+    }
+
+    // Whether the campaign has been approved and not yet closed
+    pub fn is_live(&self) -> bool {
+        matches!(self, CampaignStatus::Active | CampaignStatus::Completed)
+    }
+}
+
+impl Campaign {
+    // Move the campaign to a new status, panicking on invalid transitions
+    pub fn transition(&mut self, next: CampaignStatus) {
+        if !self.status.can_transition_to(&next) {
+            panic!("Invalid status transition");
+        }
+        self.status = next;
+    }
+
+    // Reopen a completed campaign to donations under a new target
+    pub fn reopen(&mut self, env: &Env, target_amount: i128) {
+        if self.status != CampaignStatus::Completed {
+            panic!("Only completed campaigns can be reopened");
+        }
+        validate_target(env, target_amount);
+        if target_amount <= self.raised_amount() {
+            panic_with_error!(env, Error::InvalidTarget);
+        }
+
+        self.target_amount = target_amount;
+        self.status = CampaignStatus::Active;
+    }
+
+    // Whether a new text/target edit would be accepted
+    pub fn validate_update(&self, env: &Env, target_amount: i128) {
+        if target_amount == self.target_amount {
+            return;
+        }
+        if self.goal_type == GoalType::OpenEnded {
+            panic!("Open-ended campaigns have no target");
+        }
+
+        match self.status {
+            CampaignStatus::Draft | CampaignStatus::Rejected => validate_target(env, target_amount),
+            CampaignStatus::Active => {
+                if target_amount < self.target_amount {
+                    panic!("Target amount can only be raised");
+                }
+            }
+            _ => panic!("Target can only be changed while campaign is active"),
+        }
+        if self.goal_type == GoalType::Fixed && target_amount < self.current_amount {
+            panic!("Target amount cannot be below current amount");
+        }
+    }
+
+    // Change the goal type before the campaign completes; open-ended drops the target
+    pub fn set_goal_type(&mut self, env: &Env, goal_type: GoalType) {
+        match self.status {
+            CampaignStatus::Completed | CampaignStatus::Closed => {
+                panic!("Goal type can only be changed before completion")
+            }
+            _ => {}
+        }
+
+        if goal_type == GoalType::OpenEnded {
+            self.target_amount = 0;
+        } else {
+            validate_target(env, self.target_amount);
+        }
+
+        self.goal_type = goal_type;
+    }
+
+    // Balance plus what has been paid out, the amount counted against the target
+    pub fn raised_amount(&self) -> i128 {
+        self.current_amount.saturating_add(self.withdrawn_amount)
+    }
+
+    // Whether reaching the target should complete the campaign
+    pub fn target_reached(&self) -> bool {
+        self.goal_type == GoalType::Fixed && self.raised_amount() >= self.target_amount
+    }
+
+    // Whether the recipient may withdraw while donations are still open
+    pub fn allows_partial_withdrawal(&self) -> bool {
+        self.goal_type != GoalType::Fixed
+    }
+
+    // Check a donation amount against the campaign's bounds
+    pub fn validate_donation(&self, env: &Env, amount: i128) {
+        validate_amount(env, amount);
+
+        if self.min_donation > 0 && amount < self.min_donation {
+            panic_with_error!(env, Error::BelowMinimum);
+        }
+        if self.max_donation > 0 && amount > self.max_donation {
+            panic_with_error!(env, Error::AboveMaximum);
+        }
+    }
+
+    // Set per-donation bounds, 0 disables a bound
+    pub fn set_donation_limits(&mut self, env: &Env, min_donation: i128, max_donation: i128) {
+        if min_donation < 0 || max_donation < 0 {
+            panic_with_error!(env, Error::InvalidLimits);
+        }
+        if max_donation > 0 && max_donation < min_donation {
+            panic_with_error!(env, Error::InvalidLimits);
+        }
+
+        self.min_donation = min_donation;
+        self.max_donation = max_donation;
+    }
+
+    // Add a donation to the raised amount
+    pub fn add_funds(&mut self, env: &Env, amount: i128) {
+        // Lifetime total (balance + everything paid out) must stay representable too
+        let raised = checked_add(env, self.current_amount, self.withdrawn_amount);
+        let raised = checked_add(env, raised, self.redirected_out);
+        let raised = checked_add(env, raised, self.refunded_amount);
+        checked_add(env, raised, amount);
+
+        self.current_amount = checked_add(env, self.current_amount, amount);
+    }
+
+    // Split a donation into the accepted part and the excess over target
+    pub fn split_excess(&self, env: &Env, amount: i128) -> (i128, i128) {
+        // Only a fixed target can be exceeded
+        if self.excess_policy == ExcessPolicy::AcceptAll || self.goal_type != GoalType::Fixed {
+            return (amount, 0);
+        }
+
+        let room = (self.target_amount - self.raised_amount()).max(0);
+        if amount <= room {
+            return (amount, 0);
+        }
+        if self.excess_policy == ExcessPolicy::RejectExcess {
+            panic_with_error!(env, Error::ExceedsTarget);
+        }
+
+        (room, amount - room)
+    }
+
+    // Set the excess policy, a campaign cannot redirect to itself
+    pub fn set_excess_policy(&mut self, env: &Env, policy: ExcessPolicy) {
+        if policy == ExcessPolicy::RedirectExcess(self.id) {
+            panic_with_error!(env, Error::InvalidRedirect);
+        }
+
+        self.excess_policy = policy;
+    }
+
+    // Move the whole balance to the withdrawn total, returning the amount
+    pub fn withdraw_all(&mut self, env: &Env) -> i128 {
+        let amount = self.current_amount;
+        self.withdrawn_amount = checked_add(env, self.withdrawn_amount, amount);
+        self.current_amount = 0;
+        amount
+    }
+
+    // Pay part of the balance to a beneficiary
+    pub fn withdraw(&mut self, env: &Env, amount: i128) {
+        validate_amount(env, amount);
+        if amount > self.current_amount {
+            panic!("Amount exceeds campaign balance");
+        }
+
+        self.current_amount -= amount;
+        self.withdrawn_amount = checked_add(env, self.withdrawn_amount, amount);
+    }
+
+    // Move part of the balance out to another campaign
+    pub fn redirect_out(&mut self, env: &Env, amount: i128) {
+        validate_amount(env, amount);
+        if amount > self.current_amount {
+            panic!("Amount exceeds campaign balance");
+        }
+
+        self.current_amount -= amount;
+        self.redirected_out = checked_add(env, self.redirected_out, amount);
+    }
+
+    // Return part of the balance to a donor
+    pub fn refund(&mut self, env: &Env, amount: i128) {
+        validate_amount(env, amount);
+        if amount > self.current_amount {
+            panic!("Amount exceeds campaign balance");
+        }
+
+        self.current_amount -= amount;
+        self.refunded_amount = checked_add(env, self.refunded_amount, amount);
+    }
+
+    // Receive funds moved from another campaign
+    pub fn redirect_in(&mut self, env: &Env, amount: i128) {
+        self.add_funds(env, amount);
+        self.redirected_in = checked_add(env, self.redirected_in, amount);
+    }
+
+    // Compare the recorded donations and incoming redirects with the
+    // balance plus withdrawals, outgoing redirects and refunds
+    pub fn integrity_report(&self, donation_total: i128) -> IntegrityReport {
+        let received = donation_total.checked_add(self.redirected_in);
+        let accounted = self
+            .current_amount
+            .checked_add(self.withdrawn_amount)
+            .and_then(|total| total.checked_add(self.redirected_out))
+            .and_then(|total| total.checked_add(self.refunded_amount));
+        IntegrityReport {
+            campaign_id: self.id,
+            donation_total,
+            current_amount: self.current_amount,
+            withdrawn_amount: self.withdrawn_amount,
+            redirected_in: self.redirected_in,
+            redirected_out: self.redirected_out,
+            refunded_amount: self.refunded_amount,
+            is_consistent: received.is_some()
+                && accounted == received
+                && self.current_amount >= 0
+                && self.withdrawn_amount >= 0
+                && self.redirected_in >= 0
+                && self.redirected_out >= 0
+                && self.refunded_amount >= 0,
+        }
+    }
+}
+
+// Validation
+pub fn validate_target(env: &Env, target_amount: i128) {
+    if target_amount <= 0 {
+        panic_with_error!(env, Error::InvalidTarget);
+    }
+}
+
+pub fn validate_amount(env: &Env, amount: i128) {
+    if amount <= 0 {
+        panic_with_error!(env, Error::InvalidAmount);
+    }
+}
+
+// Add two amounts, failing on overflow
+pub fn checked_add(env: &Env, a: i128, b: i128) -> i128 {
+    a.checked_add(b)
+        .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+}
+
+// Split a total by basis points. Each leg is rounded down and the dust goes
+// to the leg with the largest share (the first one on a tie).
+pub fn split_by_bps(env: &Env, total: i128, shares: &Vec<(u32, u32)>) -> Vec<(u32, i128)> {
+    validate_amount(env, total);
+    if shares.is_empty() {
+        panic_with_error!(env, Error::InvalidShares);
+    }
+
+    let mut bps_sum = 0u32;
+    let mut largest = 0u32;
+    for (i, (_, bps)) in shares.iter().enumerate() {
+        if bps == 0 || bps > BPS_DENOMINATOR {
+            panic_with_error!(env, Error::InvalidShares);
+        }
+        if bps > shares.get(largest).unwrap().1 {
+            largest = i as u32;
+        }
+        bps_sum += bps;
+    }
+    if bps_sum != BPS_DENOMINATOR {
+        panic_with_error!(env, Error::InvalidShares);
+    }
+
+    // total * bps / 10000 without overflowing the multiplication
+    let denominator = BPS_DENOMINATOR as i128;
+    let (quotient, remainder) = (total / denominator, total % denominator);
+
+    let mut legs = Vec::new(env);
+    let mut assigned = 0i128;
+    for (campaign_id, bps) in shares.iter() {
+        let bps = bps as i128;
+        let amount = quotient * bps + remainder * bps / denominator;
+        assigned += amount;
+        legs.push_back((campaign_id, amount));
+    }
+
+    let (campaign_id, amount) = legs.get(largest).unwrap();
+    legs.set(largest, (campaign_id, amount + (total - assigned)));
+
+    legs
+}
+
+// Campaign storage
+pub fn load_campaigns(env: &Env) -> Map<u32, Campaign> {
+    env.storage()
+        .instance()
+        .get(&CAMPAIGNS)
+        .unwrap_or(Map::new(env))
+}
+
+pub fn save_campaigns(env: &Env, campaigns: &Map<u32, Campaign>) {
+    env.storage().instance().set(&CAMPAIGNS, campaigns);
+}
+
+pub fn load_campaign(env: &Env, id: u32) -> Campaign {
+    load_campaigns(env)
+        .get(id)
+        .unwrap_or_else(|| panic!("Campaign not found"))
+}
+
+pub fn save_campaign(env: &Env, campaign: &Campaign) {
+    let mut campaigns = load_campaigns(env);
+    campaigns.set(campaign.id, campaign.clone());
+    save_campaigns(env, &campaigns);
+}
+
+pub fn campaign_count(env: &Env) -> u32 {
+    env.storage().instance().get(&CAMPAIGN_COUNT).unwrap_or(0)
+}
+
+// Reserve the next campaign ID
+pub fn next_campaign_id(env: &Env) -> u32 {
+    let id = campaign_count(env) + 1;
+    env.storage().instance().set(&CAMPAIGN_COUNT, &id);
+    id
+}
+
+// Campaign edit history
+pub fn load_history(env: &Env, campaign_id: u32) -> Vec<CampaignRevision> {
+    let history: Map<u32, Vec<CampaignRevision>> = env
+        .storage()
+        .instance()
+        .get(&HISTORY)
+        .unwrap_or(Map::new(env));
+
+    history.get(campaign_id).unwrap_or(Vec::new(env))
+}
+
+// Append a revision with the campaign's current values, returning its version
+pub fn record_revision(env: &Env, campaign: &Campaign, editor: Address) -> u32 {
+    let mut history: Map<u32, Vec<CampaignRevision>> = env
+        .storage()
+        .instance()
+        .get(&HISTORY)
+        .unwrap_or(Map::new(env));
+
+    let mut revisions = history.get(campaign.id).unwrap_or(Vec::new(env));
+    let version = revisions.len();
+
+    revisions.push_back(CampaignRevision {
+        version,
+        title: campaign.title.clone(),
+        description: campaign.description.clone(),
+        target_amount: campaign.target_amount,
+        editor,
+        timestamp: env.ledger().timestamp(),
+    });
+
+    history.set(campaign.id, revisions);
+    env.storage().instance().set(&HISTORY, &history);
+
+    version
+}
+
+// Storage TTL
+pub fn get_ttl_config(env: &Env) -> TtlConfig {
+    env.storage()
+        .instance()
+        .get(&TTL_CONFIG)
+        .unwrap_or(TtlConfig {
+            threshold: DEFAULT_TTL_THRESHOLD,
+            extend_to: DEFAULT_TTL_EXTEND_TO,
+        })
+}
+
+pub fn set_ttl_config(env: &Env, threshold: u32, extend_to: u32) {
+    if threshold == 0 || extend_to < threshold {
+        panic!("Extend-to must be at least the threshold");
+    }
+    if extend_to > env.storage().max_ttl() {
+        panic!("Extend-to exceeds maximum TTL");
+    }
+
+    env.storage().instance().set(
+        &TTL_CONFIG,
+        &TtlConfig {
+            threshold,
+            extend_to,
+        },
+    );
+}
+
+// Extend TTL of the instance storage holding campaigns and donations
+pub fn extend_instance_ttl(env: &Env) {
+    let config = get_ttl_config(env);
+    env.storage()
+        .instance()
+        .extend_ttl(config.threshold, config.extend_to);
+}
+
+// Extend TTL of a persistent entry such as a donation record
+pub fn extend_persistent_ttl<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    let config = get_ttl_config(env);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, config.threshold, config.extend_to);
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,324 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+use proptest::prelude::*;
+use soroban_sdk::{testutils::Address as _, vec, Address, Env, String};
+
+fn campaign(env: &Env, status: CampaignStatus) -> Campaign {
+    Campaign {
+        id: 1,
+        title: String::from_str(env, "Zakat Fitrah"),
+        description: String::from_str(env, "Zakat untuk fakir miskin"),
+        category: CampaignCategory::Zakat,
+        region: String::from_str(env, ""),
+        target_amount: 50_000_000,
+        current_amount: 20_000_000,
+        withdrawn_amount: 0,
+        redirected_in: 0,
+        redirected_out: 0,
+        refunded_amount: 0,
+        recipient: Address::generate(env),
+        status,
+        created_at: 0,
+        min_donation: 0,
+        max_donation: 0,
+        excess_policy: ExcessPolicy::AcceptAll,
+        goal_type: GoalType::Fixed,
+    }
+}
+
+#[test]
+fn test_status_transitions() {
+    use CampaignStatus::*;
+
+    assert!(Draft.can_transition_to(&PendingReview));
+    assert!(Rejected.can_transition_to(&PendingReview));
+    assert!(PendingReview.can_transition_to(&Active));
+    assert!(PendingReview.can_transition_to(&Rejected));
+    assert!(Active.can_transition_to(&Completed));
+    assert!(Active.can_transition_to(&Closed));
+    assert!(Completed.can_transition_to(&Closed));
+
+    assert!(!Draft.can_transition_to(&Active));
+    assert!(!Rejected.can_transition_to(&Active));
+    assert!(!Completed.can_transition_to(&Active));
+    assert!(!Closed.can_transition_to(&Active));
+    assert!(!Closed.can_transition_to(&Closed));
+}
+
+#[test]
+fn test_is_live() {
+    assert!(CampaignStatus::Active.is_live());
+    assert!(CampaignStatus::Completed.is_live());
+    assert!(!CampaignStatus::PendingReview.is_live());
+    assert!(!CampaignStatus::Closed.is_live());
+}
+
+#[test]
+fn test_transition() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+
+    campaign.transition(CampaignStatus::Completed);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+}
+
+#[test]
+#[should_panic(expected = "Invalid status transition")]
+fn test_invalid_transition() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Draft);
+
+    campaign.transition(CampaignStatus::Active);
+}
+
+#[test]
+fn test_validate_update() {
+    let env = Env::default();
+
+    // Text-only edits are always allowed
+    campaign(&env, CampaignStatus::Closed).validate_update(&env, 50_000_000);
+    // Raising while active
+    campaign(&env, CampaignStatus::Active).validate_update(&env, 60_000_000);
+    // Drafts can set any positive target
+    campaign(&env, CampaignStatus::Draft).validate_update(&env, 30_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Target amount can only be raised")]
+fn test_validate_update_lower_target() {
+    let env = Env::default();
+
+    campaign(&env, CampaignStatus::Active).validate_update(&env, 40_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Target can only be changed while campaign is active")]
+fn test_validate_update_completed_target() {
+    let env = Env::default();
+
+    campaign(&env, CampaignStatus::Completed).validate_update(&env, 60_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_validate_amount() {
+    let env = Env::default();
+    validate_amount(&env, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_validate_target() {
+    let env = Env::default();
+    validate_target(&env, -1);
+}
+
+#[test]
+fn test_validate_donation_limits() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_donation_limits(&env, 10_000, 1_000_000);
+
+    campaign.validate_donation(&env, 10_000);
+    campaign.validate_donation(&env, 1_000_000);
+
+    // Max of 0 leaves the upper bound open
+    campaign.set_donation_limits(&env, 10_000, 0);
+    campaign.validate_donation(&env, i128::MAX);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_validate_donation_below_minimum() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_donation_limits(&env, 10_000, 0);
+    campaign.validate_donation(&env, 9_999);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_validate_donation_above_maximum() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_donation_limits(&env, 0, 1_000_000);
+    campaign.validate_donation(&env, 1_000_001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_set_donation_limits_max_below_min() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_donation_limits(&env, 1_000, 999);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_add_funds_overflow() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.add_funds(&env, i128::MAX);
+}
+
+#[test]
+fn test_integrity_report() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Completed);
+
+    assert!(campaign.integrity_report(20_000_000).is_consistent);
+    assert!(!campaign.integrity_report(19_999_999).is_consistent);
+
+    assert_eq!(campaign.withdraw_all(&env), 20_000_000);
+    let report = campaign.integrity_report(20_000_000);
+    assert_eq!(report.current_amount, 0);
+    assert_eq!(report.withdrawn_amount, 20_000_000);
+    assert!(report.is_consistent);
+
+    // Overflowing books can never be consistent
+    campaign.withdrawn_amount = i128::MAX;
+    campaign.current_amount = 1;
+    assert!(!campaign.integrity_report(i128::MAX).is_consistent);
+}
+
+#[test]
+fn test_split_excess() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+
+    // 30M of room left under the 50M target
+    assert_eq!(campaign.split_excess(&env, 40_000_000), (40_000_000, 0));
+
+    campaign.set_excess_policy(&env, ExcessPolicy::RefundExcess);
+    assert_eq!(campaign.split_excess(&env, 30_000_000), (30_000_000, 0));
+    assert_eq!(campaign.split_excess(&env, 40_000_000), (30_000_000, 10_000_000));
+
+    // Withdrawn funds count as raised
+    campaign.withdraw_all(&env);
+    assert_eq!(campaign.split_excess(&env, 40_000_000), (30_000_000, 10_000_000));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_split_excess_rejected() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_excess_policy(&env, ExcessPolicy::RejectExcess);
+    campaign.split_excess(&env, 30_000_001);
+}
+
+#[test]
+fn test_goal_types() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.current_amount = 30_000_000;
+    campaign.withdraw(&env, 30_000_000);
+    campaign.current_amount = 20_000_000;
+    // Paid-out funds count towards the target like in split_excess
+    assert!(campaign.target_reached());
+    assert!(!campaign.allows_partial_withdrawal());
+
+    // Flexible targets are display-only and may sit below the balance
+    campaign.set_goal_type(&env, GoalType::Flexible);
+    assert!(!campaign.target_reached());
+    assert!(campaign.allows_partial_withdrawal());
+    campaign.current_amount = 80_000_000;
+    campaign.validate_update(&env, 60_000_000);
+
+    campaign.set_goal_type(&env, GoalType::OpenEnded);
+    assert_eq!(campaign.target_amount, 0);
+    assert!(!campaign.target_reached());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_goal_type_requires_target() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.set_goal_type(&env, GoalType::OpenEnded);
+
+    // Leaving open-ended needs a target, which it no longer has
+    campaign.set_goal_type(&env, GoalType::Fixed);
+}
+
+#[test]
+fn test_reopen() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Completed);
+
+    campaign.reopen(&env, 80_000_000);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.target_amount, 80_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Only completed campaigns can be reopened")]
+fn test_reopen_active() {
+    let env = Env::default();
+    let mut campaign = campaign(&env, CampaignStatus::Active);
+    campaign.reopen(&env, 80_000_000);
+}
+
+#[test]
+fn test_split_by_bps() {
+    let env = Env::default();
+
+    let legs = split_by_bps(&env, 100, &vec![&env, (1, 3_333), (2, 3_334), (3, 3_333)]);
+    assert_eq!(legs, vec![&env, (1, 33), (2, 34), (3, 33)]);
+
+    // Dust of 2 goes to the first of the tied largest shares
+    let legs = split_by_bps(&env, 101, &vec![&env, (1, 2_500), (2, 5_000), (3, 2_500)]);
+    assert_eq!(legs, vec![&env, (1, 25), (2, 51), (3, 25)]);
+
+    // No intermediate overflow for large totals
+    let legs = split_by_bps(&env, i128::MAX, &vec![&env, (1, 5_000), (2, 5_000)]);
+    assert_eq!(legs.get(0).unwrap().1, i128::MAX / 2 + 1);
+    assert_eq!(legs.get(1).unwrap().1, i128::MAX / 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_split_by_bps_zero_share() {
+    let env = Env::default();
+    split_by_bps(&env, 100, &vec![&env, (1, 10_000), (2, 0)]);
+}
+
+proptest! {
+    #[test]
+    fn prop_split_by_bps_is_exact(
+        total in 1i128..=i128::MAX,
+        weights in prop::collection::vec(1u32..100, 1..6),
+    ) {
+        let env = Env::default();
+
+        // Scale arbitrary weights to shares summing to 10000
+        let weight_sum: u32 = weights.iter().sum();
+        let mut shares = Vec::new(&env);
+        let mut bps_left = BPS_DENOMINATOR;
+        for (i, weight) in weights.iter().enumerate() {
+            let bps = if i == weights.len() - 1 {
+                bps_left
+            } else {
+                (weight * BPS_DENOMINATOR / weight_sum).max(1)
+            };
+            bps_left -= bps;
+            shares.push_back((i as u32, bps));
+        }
+        prop_assume!(shares.iter().all(|(_, bps)| bps > 0));
+
+        let legs = split_by_bps(&env, total, &shares);
+
+        // Legs add up exactly and each is within the dust of its exact share
+        let mut sum = 0i128;
+        for ((_, amount), (_, bps)) in legs.iter().zip(shares.iter()) {
+            let exact_floor = total / 10_000 * bps as i128 + total % 10_000 * bps as i128 / 10_000;
+            prop_assert!(amount >= exact_floor);
+            prop_assert!(amount - exact_floor < shares.len() as i128);
+            sum += amount;
+        }
+        prop_assert_eq!(sum, total);
+    }
+}
@@ -1,334 +1,3449 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, symbol_short, Vec, Map};
+use donation_core::{
+    checked_add, extend_instance_ttl, extend_persistent_ttl, load_campaign, load_campaigns,
+    record_revision, save_campaign, CampaignUpdated, CATEGORIES,
+};
+use soroban_sdk::{
+    contract, contractevent, contractimpl, contracttype, panic_with_error, token, Address, BytesN,
+    Env, String, symbol_short, Vec, Map,
+};
+
+pub use donation_core::{
+    Campaign, CampaignCategory, CampaignRevision, CampaignStatus, Donation, Error,
+    ExcessPolicy, GoalType, IntegrityReport, TtlConfig,
+};
 
 // Storage keys
-const CAMPAIGNS: soroban_sdk::Symbol = symbol_short!("CAMPAIGNS");
-const CAMPAIGN_COUNT: soroban_sdk::Symbol = symbol_short!("COUNT");
-const DONATIONS: soroban_sdk::Symbol = symbol_short!("DONATIONS");
 const ADMIN: soroban_sdk::Symbol = symbol_short!("ADMIN");
+const DONATIONS: soroban_sdk::Symbol = symbol_short!("DONS");
+const DONATION_COUNT: soroban_sdk::Symbol = symbol_short!("DONCOUNT");
+const DONOR_INDEX: soroban_sdk::Symbol = symbol_short!("DONORIDX");
+const CAMPAIGN_INDEX: soroban_sdk::Symbol = symbol_short!("CAMPIDX");
+const CATEGORY_INDEX: soroban_sdk::Symbol = symbol_short!("CATIDX");
+const STATUS_INDEX: soroban_sdk::Symbol = symbol_short!("STATIDX");
+const RECIPIENT_INDEX: soroban_sdk::Symbol = symbol_short!("RECIDX");
+const CATEGORY_COUNTS: soroban_sdk::Symbol = symbol_short!("CATCOUNT");
+const STRETCH_GOALS: soroban_sdk::Symbol = symbol_short!("STRETCH");
+const REDIRECT_GRACE: soroban_sdk::Symbol = symbol_short!("RDRGRACE");
+const PENDING_REDIRECTS: soroban_sdk::Symbol = symbol_short!("RDRPEND");
+const REDIRECT_LOG: soroban_sdk::Symbol = symbol_short!("RDRLOG");
+const POOLS: soroban_sdk::Symbol = symbol_short!("POOLS");
+const POOL_DONATIONS: soroban_sdk::Symbol = symbol_short!("POOLDONS");
+const POOL_ALLOCATIONS: soroban_sdk::Symbol = symbol_short!("POOLALLOC");
+const POOL_CURSOR: soroban_sdk::Symbol = symbol_short!("POOLCUR");
+const POOL_DONOR_INDEX: soroban_sdk::Symbol = symbol_short!("POOLDONOR");
+const POOL_TRACES: soroban_sdk::Symbol = symbol_short!("POOLTRACE");
+const TOKEN: soroban_sdk::Symbol = symbol_short!("TOKEN");
+const BATCH_COUNT: soroban_sdk::Symbol = symbol_short!("BATCHCNT");
+const BATCH_INDEX: soroban_sdk::Symbol = symbol_short!("BATCHIDX");
+const FITRAH: soroban_sdk::Symbol = symbol_short!("FITRAH");
+const QURBAN: soroban_sdk::Symbol = symbol_short!("QURBAN");
+const MODES: soroban_sdk::Symbol = symbol_short!("MODES");
+const RETURNS: soroban_sdk::Symbol = symbol_short!("RETURNS");
+const WAQF: soroban_sdk::Symbol = symbol_short!("WAQF");
+const LOAN_TERMS: soroban_sdk::Symbol = symbol_short!("LOANTERM");
+const LOANS: soroban_sdk::Symbol = symbol_short!("LOANS");
+const SCHEDULES: soroban_sdk::Symbol = symbol_short!("SCHEDULE");
+const VERIFIERS: soroban_sdk::Symbol = symbol_short!("VERIFIER");
+const VENDORS: soroban_sdk::Symbol = symbol_short!("VENDORS");
+const INVOICES: soroban_sdk::Symbol = symbol_short!("INVOICES");
+const VENDOR_PAYMENTS: soroban_sdk::Symbol = symbol_short!("VENDPAY");
+const REPORTS: soroban_sdk::Symbol = symbol_short!("REPORTS");
+const PAYOUTS: soroban_sdk::Symbol = symbol_short!("PAYOUTS");
+const REPORT_DEADLINE: soroban_sdk::Symbol = symbol_short!("REPDEADLN");
+
+// Default wait between a redirect notice and the move (30 days, in seconds)
+const DEFAULT_REDIRECT_GRACE: u64 = 30 * 24 * 60 * 60;
+// Shortest grace period the admin may set, so donors always get notice (1 day)
+const MIN_REDIRECT_GRACE: u64 = 24 * 60 * 60;
+// Time a recipient has to report spending after a payout (30 days)
+const DEFAULT_REPORT_DEADLINE: u64 = 30 * 24 * 60 * 60;
 
 #[contract]
 pub struct ZakatContract;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum CampaignCategory {
-    Zakat,
-    Pendidikan,
-    Kesehatan,
-    BencanaAlam,
-    UMKM,
+pub struct CategoryTotal {
+    pub category: CampaignCategory,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CategoryCount {
+    pub category: CampaignCategory,
+    pub count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CampaignTotal {
+    pub campaign_id: u32,
+    pub category: CampaignCategory,
+    pub amount: i128,
+    pub donation_count: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AnnualStatement {
+    pub donor: Address,
+    pub year_start: u64,
+    pub year_end: u64,
+    pub total_amount: i128,
+    pub donation_count: u32,
+    pub by_category: Vec<CategoryTotal>,
+    pub by_campaign: Vec<CampaignTotal>,
+}
+
+// Extra target appended after the original goal (reached_at 0 = pending)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StretchGoal {
+    pub target_amount: i128,
+    pub added_at: u64,
+    pub reached_at: u64,
+}
+
+// Donation to a category pool; allocated tracks how much has reached campaigns
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolDonation {
+    pub category: CampaignCategory,
+    pub donor: Address,
+    pub amount: i128,
+    pub allocated: i128,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllocationSource {
+    pub donor: Address,
+    pub amount: i128,
+}
+
+// Pooled funds moved into a campaign, with the donors they came from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolAllocation {
+    pub category: CampaignCategory,
+    pub campaign_id: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub sources: Vec<AllocationSource>,
+}
+
+// Where part of a donor's pool donation ended up
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolTrace {
+    pub category: CampaignCategory,
+    pub campaign_id: u32,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+// Special way a campaign takes donations, a campaign has at most one
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CampaignMode {
+    Fitrah,
+    Qurban,
+    Waqf,
+    Loan,
+    Scholarship,
+    VendorOnly,
+}
+
+// Recorded donation handed back to the donor later, e.g. an unfilled qurban share
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DonationReturn {
+    pub campaign_id: u32,
+    pub amount: i128,
+    pub is_anonymous: bool,
+    pub timestamp: u64,
+}
+
+// Zakat fitrah mode: fixed amount per person, collected inside a window
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FitrahConfig {
+    pub amount_per_person: i128,
+    pub window_start: u64,
+    pub window_end: u64,
+    pub persons_covered: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FundType {
+    Standard,
+    // Principal is kept forever, only recorded returns are paid out
+    Waqf,
+}
+
+// Returns of a waqf campaign, kept apart from its principal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WaqfYield {
+    pub recorded: i128,
+    pub withdrawn: i128,
 }
 
+// Where qard hasan repayments go
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum CampaignStatus {
-    Active,
-    Completed,
-    Closed,
+pub enum RepaymentMode {
+    // Back into the category pool to fund the next business
+    Revolving,
+    // Back to the campaign's donors in proportion to what they gave
+    ReturnToDonors,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Campaign {
+pub struct LoanTerms {
+    pub mode: RepaymentMode,
+    pub installments: u32,
+    // Seconds between installments
+    pub interval: u64,
+}
+
+// Interest-free loan created when a loan campaign is disbursed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Loan {
+    pub campaign_id: u32,
+    pub borrower: Address,
+    pub principal: i128,
+    pub repaid: i128,
+    pub disbursed_at: u64,
+    pub terms: LoanTerms,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanStatus {
+    pub outstanding: i128,
+    // Amount that should have been repaid by now
+    pub due: i128,
+    // Zero once every installment date has passed
+    pub next_due_at: u64,
+    pub is_overdue: bool,
+}
+
+// One scheduled scholarship payout, held in escrow until claimed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Installment {
     pub id: u32,
-    pub title: String,
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub due_at: u64,
+    // Verifier confirmed the beneficiary is still enrolled
+    pub attested: bool,
+    pub claimed: bool,
+}
+
+// Campaign funds paid straight to an approved vendor against an invoice
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VendorPayment {
+    pub campaign_id: u32,
+    pub vendor: Address,
+    pub amount: i128,
+    pub invoice_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+// How a recipient used withdrawn funds, with a hash of the proof document
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingReport {
+    pub campaign_id: u32,
+    pub amount_spent: i128,
     pub description: String,
-    pub category: CampaignCategory,
+    pub doc_hash: BytesN<32>,
+    pub timestamp: u64,
+}
+
+// Everything paid out of a campaign: withdrawals, vendor payments,
+// scholarship claims and waqf returns
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PayoutRecord {
+    pub total: i128,
+    pub last_at: u64,
+}
+
+// Reported spending against what was paid out
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SpendingSummary {
+    pub campaign_id: u32,
+    // Total of every payout, see PayoutRecord
+    pub withdrawn_amount: i128,
+    pub reported_amount: i128,
+    pub unreported_amount: i128,
+    // Zero when never withdrawn or reported
+    pub last_withdrawal_at: u64,
+    pub last_report_at: u64,
+    // No report since the last withdrawal and the deadline has passed
+    pub is_overdue: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum QurbanUnitStatus {
+    Open,
+    Full,
+    // Participants moved into other units when the campaign closed
+    Merged,
+    // Participants refunded when the campaign closed
+    Refunded,
+}
+
+// One animal (a goat with 1 slot, a cow or camel with 7), price split evenly per slot
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QurbanUnit {
+    pub id: u32,
+    pub animal: String,
+    pub price: i128,
+    pub slots: u32,
+    pub participants: Vec<Address>,
+    pub status: QurbanUnitStatus,
+}
+
+// Result of a batch or split donation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchReceipt {
+    pub batch_id: u32,
+    pub refunded: i128,
+}
+
+// Contract token balance against what campaigns and pools hold. Tokens
+// sent straight to the contract show up as surplus, not as a mismatch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustodyReport {
+    pub token_balance: i128,
+    pub held_amount: i128,
+    pub surplus: i128,
+    pub is_consistent: bool,
+}
+
+// Redirect announced to donors, executable once the grace period passes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingRedirect {
+    pub to_campaign: u32,
+    pub amount: i128,
+    pub announced_at: u64,
+    pub executable_at: u64,
+}
+
+// Executed redirect, listed in the history of both campaigns
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundRedirect {
+    pub from_campaign: u32,
+    pub to_campaign: u32,
+    pub amount: i128,
+    pub announced_at: u64,
+    pub executed_at: u64,
+}
+
+// Events
+#[contractevent(topics = ["donate"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DonationMade {
+    #[topic]
+    pub campaign_id: u32,
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["stretch"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StretchGoalReached {
+    #[topic]
+    pub campaign_id: u32,
+    pub index: u32,
     pub target_amount: i128,
-    pub current_amount: i128,
-    pub recipient: Address,
-    pub status: CampaignStatus,
-    pub created_at: u64,
 }
 
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Donation {
-    pub campaign_id: u32,
-    pub donor: Address,
-    pub amount: i128,
-    pub timestamp: u64,
-    pub is_anonymous: bool,
-}
+#[contractevent(topics = ["pool"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolDonationMade {
+    #[topic]
+    pub category: CampaignCategory,
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["allocate"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolAllocated {
+    #[topic]
+    pub category: CampaignCategory,
+    #[topic]
+    pub campaign_id: u32,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["fund_notice"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedirectAnnounced {
+    #[topic]
+    pub from_campaign: u32,
+    #[topic]
+    pub to_campaign: u32,
+    pub amount: i128,
+    pub executable_at: u64,
+}
+
+#[contractevent(topics = ["fund_redirect"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FundsRedirected {
+    #[topic]
+    pub from_campaign: u32,
+    #[topic]
+    pub to_campaign: u32,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["waqf_yield"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WaqfYieldRecorded {
+    #[topic]
+    pub campaign_id: u32,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["loan"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanDisbursed {
+    #[topic]
+    pub campaign_id: u32,
+    pub borrower: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["repay"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanRepaid {
+    #[topic]
+    pub campaign_id: u32,
+    pub borrower: Address,
+    pub amount: i128,
+    pub outstanding: i128,
+}
+
+#[contractevent(topics = ["installment"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstallmentClaimed {
+    #[topic]
+    pub campaign_id: u32,
+    pub installment_id: u32,
+    pub beneficiary: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["vendor_pay"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VendorPaid {
+    #[topic]
+    pub campaign_id: u32,
+    #[topic]
+    pub vendor: Address,
+    pub amount: i128,
+    pub invoice_hash: BytesN<32>,
+}
+
+#[contractevent(topics = ["report"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportSubmitted {
+    #[topic]
+    pub campaign_id: u32,
+    pub amount_spent: i128,
+    pub doc_hash: BytesN<32>,
+}
+
+#[contractevent(topics = ["qurban_full"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QurbanUnitFull {
+    #[topic]
+    pub campaign_id: u32,
+    pub unit_id: u32,
+}
+
+#[contractevent(topics = ["qurban_refund"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QurbanRefunded {
+    #[topic]
+    pub campaign_id: u32,
+    pub unit_id: u32,
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["refund"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExcessRefunded {
+    #[topic]
+    pub campaign_id: u32,
+    pub donor: Address,
+    pub amount: i128,
+}
+
+#[contractevent(topics = ["redirect"], data_format = "vec")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExcessRedirected {
+    #[topic]
+    pub campaign_id: u32,
+    pub target_campaign_id: u32,
+    pub amount: i128,
+}
+
+#[contractimpl]
+impl ZakatContract {
+    
+    // Initialize contract with admin
+    pub fn initialize(env: Env, admin: Address) {
+        admin.require_auth();
+        
+        env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&donation_core::CAMPAIGN_COUNT, &0u32);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Set storage TTL thresholds (only admin)
+    pub fn set_ttl_config(env: Env, admin: Address, threshold: u32, extend_to: u32) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can configure TTL");
+        }
+
+        donation_core::set_ttl_config(&env, threshold, extend_to);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get storage TTL thresholds
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        donation_core::get_ttl_config(&env)
+    }
+
+    // Keep campaign data alive (anyone can call)
+    pub fn bump_campaign(env: Env, campaign_id: u32) {
+        let campaign = load_campaign(&env, campaign_id);
+
+        if campaign.status == CampaignStatus::Closed {
+            panic!("Campaign is closed");
+        }
+
+        let campaign_key = (CAMPAIGN_INDEX, campaign_id);
+        if env.storage().persistent().has(&campaign_key) {
+            extend_persistent_ttl(&env, &campaign_key);
+        }
+
+        extend_instance_ttl(&env);
+    }
+
+    // Create new campaign (only admin)
+    pub fn create_campaign(
+        env: Env,
+        admin: Address,
+        title: String,
+        description: String,
+        category: CampaignCategory,
+        target_amount: i128,
+        recipient: Address,
+    ) -> u32 {
+        admin.require_auth();
+
+        // Verify admin
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can create campaigns");
+        }
+
+        // Validate
+        donation_core::validate_target(&env, target_amount);
+
+        // Reserve next ID
+        let new_id = donation_core::next_campaign_id(&env);
+
+        // Create campaign
+        let campaign = Campaign {
+            id: new_id,
+            title,
+            description,
+            category,
+            region: String::from_str(&env, ""),
+            target_amount,
+            current_amount: 0,
+            withdrawn_amount: 0,
+            redirected_in: 0,
+            redirected_out: 0,
+            refunded_amount: 0,
+            recipient,
+            status: CampaignStatus::Active,
+            created_at: env.ledger().timestamp(),
+            min_donation: 0,
+            max_donation: 0,
+            excess_policy: ExcessPolicy::AcceptAll,
+            goal_type: GoalType::Fixed,
+        };
+
+        // Store campaign and record the initial version in the history
+        save_campaign(&env, &campaign);
+        record_revision(&env, &campaign, admin);
+
+        // Add to category and recipient indexes
+        let mut category_index: Map<CampaignCategory, Vec<u32>> = env.storage()
+            .instance()
+            .get(&CATEGORY_INDEX)
+            .unwrap_or(Map::new(&env));
+
+        let mut ids = category_index.get(campaign.category.clone()).unwrap_or(Vec::new(&env));
+        ids.push_back(new_id);
+        category_index.set(campaign.category.clone(), ids);
+        env.storage().instance().set(&CATEGORY_INDEX, &category_index);
+
+        let mut recipient_index: Map<Address, Vec<u32>> = env.storage()
+            .instance()
+            .get(&RECIPIENT_INDEX)
+            .unwrap_or(Map::new(&env));
+
+        let mut ids = recipient_index.get(campaign.recipient.clone()).unwrap_or(Vec::new(&env));
+        ids.push_back(new_id);
+        recipient_index.set(campaign.recipient.clone(), ids);
+        env.storage().instance().set(&RECIPIENT_INDEX, &recipient_index);
+
+        Self::index_status(&env, &campaign, None);
+
+        extend_instance_ttl(&env);
+
+        new_id
+    }
+
+    // Update campaign metadata (only admin)
+    pub fn update_campaign(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        title: String,
+        description: String,
+        target_amount: i128,
+    ) -> u32 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can update campaigns");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+
+        // Text can always be edited, the target only while active
+        campaign.validate_update(&env, target_amount);
+        if target_amount != campaign.target_amount {
+            Self::require_not_qurban(&env, campaign_id);
+        }
+
+        campaign.title = title;
+        campaign.description = description;
+        campaign.target_amount = target_amount;
+
+        // Store and append new version to the history
+        save_campaign(&env, &campaign);
+        let version = record_revision(&env, &campaign, admin);
+
+        CampaignUpdated {
+            campaign_id,
+            version,
+            target_amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        version
+    }
+
+    // Get edit history of a campaign
+    pub fn get_campaign_history(env: Env, campaign_id: u32) -> Vec<CampaignRevision> {
+        donation_core::load_history(&env, campaign_id)
+    }
+
+    // Donate to campaign
+    pub fn donate(
+        env: Env,
+        donor: Address,
+        campaign_id: u32,
+        amount: i128,
+        is_anonymous: bool,
+    ) -> i128 {
+        donor.require_auth();
+
+        Self::require_plain_donations(&env, campaign_id);
+
+        // Returns the part refunded under RefundExcess
+        let refunded = Self::apply_donation(&env, &donor, campaign_id, amount, is_anonymous, 0, false);
+
+        // Refunded excess is simply never taken
+        Self::collect(&env, &donor, amount - refunded);
+
+        extend_instance_ttl(&env);
+
+        refunded
+    }
+
+    // Donate to several campaigns in one call. Every leg is checked before
+    // any is recorded and the tokens move in a single transfer; any failing
+    // leg reverts the whole batch.
+    pub fn donate_batch(
+        env: Env,
+        donor: Address,
+        donations: Vec<(u32, i128)>,
+        is_anonymous: bool,
+    ) -> BatchReceipt {
+        donor.require_auth();
+
+        let receipt = Self::apply_batch(&env, &donor, &donations, is_anonymous);
+
+        extend_instance_ttl(&env);
+
+        receipt
+    }
+
+    // Split one amount across campaigns by basis points (shares sum to 10000)
+    pub fn donate_split(
+        env: Env,
+        donor: Address,
+        total: i128,
+        shares: Vec<(u32, u32)>,
+        is_anonymous: bool,
+    ) -> BatchReceipt {
+        donor.require_auth();
+
+        // Shares too small to round to a whole unit are left out of the batch
+        let mut legs = Vec::new(&env);
+        for (campaign_id, amount) in donation_core::split_by_bps(&env, total, &shares).iter() {
+            if amount > 0 {
+                legs.push_back((campaign_id, amount));
+            }
+        }
+        let receipt = Self::apply_batch(&env, &donor, &legs, is_anonymous);
+
+        extend_instance_ttl(&env);
+
+        receipt
+    }
+
+    // Put a zakat campaign in fitrah mode (only admin)
+    pub fn set_fitrah(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        amount_per_person: i128,
+        window_start: u64,
+        window_end: u64,
+    ) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can configure fitrah");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.category != CampaignCategory::Zakat {
+            panic!("Fitrah mode is only for zakat campaigns");
+        }
+        donation_core::validate_amount(&env, amount_per_person);
+        if window_start >= window_end {
+            panic!("Invalid collection window");
+        }
+
+        Self::set_mode(&env, campaign_id, CampaignMode::Fitrah);
+
+        // Reconfiguring keeps the persons already covered
+        let mut configs: Map<u32, FitrahConfig> = env.storage()
+            .instance()
+            .get(&FITRAH)
+            .unwrap_or(Map::new(&env));
+        let persons_covered = configs.get(campaign_id).map_or(0, |c| c.persons_covered);
+        configs.set(
+            campaign_id,
+            FitrahConfig {
+                amount_per_person,
+                window_start,
+                window_end,
+                persons_covered,
+            },
+        );
+        env.storage().instance().set(&FITRAH, &configs);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get a campaign's fitrah configuration, if it is in fitrah mode
+    pub fn get_fitrah(env: Env, campaign_id: u32) -> Option<FitrahConfig> {
+        let configs: Map<u32, FitrahConfig> = env.storage()
+            .instance()
+            .get(&FITRAH)
+            .unwrap_or(Map::new(&env));
+
+        configs.get(campaign_id)
+    }
+
+    // Pay zakat fitrah for a number of persons during the collection window
+    pub fn donate_fitrah(env: Env, donor: Address, campaign_id: u32, persons: u32) -> i128 {
+        donor.require_auth();
+
+        let mut configs: Map<u32, FitrahConfig> = env.storage()
+            .instance()
+            .get(&FITRAH)
+            .unwrap_or(Map::new(&env));
+        let mut config = configs
+            .get(campaign_id)
+            .unwrap_or_else(|| panic!("Campaign is not in fitrah mode"));
+
+        let now = env.ledger().timestamp();
+        if now < config.window_start || now > config.window_end {
+            panic!("Outside the fitrah collection window");
+        }
+        if persons == 0 {
+            panic_with_error!(&env, Error::InvalidAmount);
+        }
+
+        let amount = config
+            .amount_per_person
+            .checked_mul(persons as i128)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+
+        // Only whole persons are accepted, whatever does not fit under
+        // the target is refunded rather than redirected
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.status != CampaignStatus::Active {
+            panic!("Campaign is not active");
+        }
+        let (accepted, _) = campaign.split_excess(&env, amount);
+        let paid_persons = (accepted / config.amount_per_person) as u32;
+        let paid = config.amount_per_person * paid_persons as i128;
+        if paid > 0 {
+            Self::apply_donation(&env, &donor, campaign_id, paid, false, 0, false);
+            Self::collect(&env, &donor, paid);
+        }
+
+        let refunded = amount - paid;
+        if refunded > 0 {
+            ExcessRefunded {
+                campaign_id,
+                donor: donor.clone(),
+                amount: refunded,
+            }
+            .publish(&env);
+        }
+
+        config.persons_covered = config
+            .persons_covered
+            .checked_add(paid_persons)
+            .unwrap_or_else(|| panic_with_error!(&env, Error::Overflow));
+        configs.set(campaign_id, config);
+        env.storage().instance().set(&FITRAH, &configs);
+
+        extend_instance_ttl(&env);
+
+        refunded
+    }
+
+    // Get the number of persons whose zakat fitrah has been paid
+    pub fn get_persons_covered(env: Env, campaign_id: u32) -> u32 {
+        Self::get_fitrah(env, campaign_id).map_or(0, |c| c.persons_covered)
+    }
+
+    // Add an animal unit to a qurban campaign (only admin). The campaign
+    // target follows the total price of its units.
+    pub fn add_qurban_unit(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        animal: String,
+        price: i128,
+        slots: u32,
+    ) -> u32 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can add qurban units");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        if campaign.status != CampaignStatus::Active {
+            panic!("Campaign is not active");
+        }
+        if campaign.goal_type != GoalType::Fixed {
+            panic!("Qurban campaigns need a fixed target");
+        }
+        if !Self::get_stretch_goals(env.clone(), campaign_id).is_empty() {
+            panic!("Campaigns with stretch goals cannot hold qurban units");
+        }
+
+        donation_core::validate_amount(&env, price);
+        if slots == 0 || price % slots as i128 != 0 {
+            panic!("Price must split evenly across slots");
+        }
+
+        Self::set_mode(&env, campaign_id, CampaignMode::Qurban);
+
+        let mut all_units: Map<u32, Vec<QurbanUnit>> = env.storage()
+            .instance()
+            .get(&QURBAN)
+            .unwrap_or(Map::new(&env));
+        let mut units = match all_units.get(campaign_id) {
+            Some(units) => units,
+            None => {
+                // Earlier plain donations would not belong to any unit
+                if campaign.current_amount != 0 || campaign.withdrawn_amount != 0 {
+                    panic!("Qurban units must be added before donations");
+                }
+                campaign.target_amount = 0;
+                Vec::new(&env)
+            }
+        };
+
+        let unit_id = units.len();
+        units.push_back(QurbanUnit {
+            id: unit_id,
+            animal,
+            price,
+            slots,
+            participants: Vec::new(&env),
+            status: QurbanUnitStatus::Open,
+        });
+        all_units.set(campaign_id, units);
+        env.storage().instance().set(&QURBAN, &all_units);
+
+        campaign.target_amount = checked_add(&env, campaign.target_amount, price);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+
+        unit_id
+    }
+
+    // Take one slot of a qurban unit
+    pub fn join_qurban(env: Env, donor: Address, campaign_id: u32, unit_id: u32) {
+        donor.require_auth();
+
+        let mut all_units: Map<u32, Vec<QurbanUnit>> = env.storage()
+            .instance()
+            .get(&QURBAN)
+            .unwrap_or(Map::new(&env));
+        let mut units = all_units
+            .get(campaign_id)
+            .unwrap_or_else(|| panic!("Campaign has no qurban units"));
+        let mut unit = units
+            .get(unit_id)
+            .unwrap_or_else(|| panic!("Qurban unit not found"));
+        if unit.status != QurbanUnitStatus::Open {
+            panic!("Qurban unit is full");
+        }
+
+        // A share must never be partly refunded or redirected
+        let share = unit.price / unit.slots as i128;
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.split_excess(&env, share).1 != 0 {
+            panic!("Qurban share exceeds campaign target");
+        }
+
+        Self::apply_donation(&env, &donor, campaign_id, share, false, 0, false);
+        Self::collect(&env, &donor, share);
+
+        unit.participants.push_back(donor);
+        if unit.participants.len() == unit.slots {
+            unit.status = QurbanUnitStatus::Full;
+            QurbanUnitFull {
+                campaign_id,
+                unit_id,
+            }
+            .publish(&env);
+        }
+        units.set(unit_id, unit);
+        all_units.set(campaign_id, units);
+        env.storage().instance().set(&QURBAN, &all_units);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the qurban units of a campaign
+    pub fn get_qurban_units(env: Env, campaign_id: u32) -> Vec<QurbanUnit> {
+        let all_units: Map<u32, Vec<QurbanUnit>> = env.storage()
+            .instance()
+            .get(&QURBAN)
+            .unwrap_or(Map::new(&env));
+
+        all_units.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Get the mode a campaign takes donations in, if any
+    pub fn get_campaign_mode(env: Env, campaign_id: u32) -> Option<CampaignMode> {
+        let modes: Map<u32, CampaignMode> = env.storage()
+            .instance()
+            .get(&MODES)
+            .unwrap_or(Map::new(&env));
+
+        modes.get(campaign_id)
+    }
+
+    // Get donations handed back to a donor (public ones only)
+    pub fn get_donor_returns(env: Env, donor: Address) -> Vec<DonationReturn> {
+        let mut result = Vec::new(&env);
+        for entry in Self::donor_returns(&env, &donor).iter() {
+            if !entry.is_anonymous {
+                result.push_back(entry);
+            }
+        }
+
+        result
+    }
+
+    // Set whether a campaign is a regular fund or a waqf (only admin).
+    // Waqf is permanent and must be chosen before any funds leave.
+    pub fn set_fund_type(env: Env, admin: Address, campaign_id: u32, fund_type: FundType) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set fund type");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        let mut funds: Map<u32, WaqfYield> = env.storage()
+            .instance()
+            .get(&WAQF)
+            .unwrap_or(Map::new(&env));
+
+        match fund_type {
+            FundType::Standard => {
+                if funds.contains_key(campaign_id) {
+                    panic!("Waqf principal is permanent");
+                }
+            }
+            FundType::Waqf => {
+                if funds.contains_key(campaign_id) {
+                    return;
+                }
+                // Zakat has to reach its recipients, it cannot be held as principal
+                if campaign.category == CampaignCategory::Zakat {
+                    panic!("Zakat campaigns cannot be waqf");
+                }
+                if campaign.withdrawn_amount != 0
+                    || campaign.redirected_out != 0
+                    || campaign.refunded_amount != 0
+                {
+                    panic!("Waqf must be set before funds are paid out");
+                }
+                Self::set_mode(&env, campaign_id, CampaignMode::Waqf);
+                funds.set(campaign_id, WaqfYield { recorded: 0, withdrawn: 0 });
+                env.storage().instance().set(&WAQF, &funds);
+            }
+        }
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn get_fund_type(env: Env, campaign_id: u32) -> FundType {
+        if Self::is_waqf(&env, campaign_id) {
+            FundType::Waqf
+        } else {
+            FundType::Standard
+        }
+    }
+
+    // Bring in returns earned on a waqf's principal (only admin)
+    pub fn record_yield(env: Env, admin: Address, campaign_id: u32, amount: i128) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can record yield");
+        }
+
+        donation_core::validate_amount(&env, amount);
+        let mut funds: Map<u32, WaqfYield> = env.storage()
+            .instance()
+            .get(&WAQF)
+            .unwrap_or(Map::new(&env));
+        let mut fund = funds
+            .get(campaign_id)
+            .unwrap_or_else(|| panic!("Campaign is not a waqf"));
+
+        fund.recorded = checked_add(&env, fund.recorded, amount);
+        funds.set(campaign_id, fund);
+        env.storage().instance().set(&WAQF, &funds);
+
+        Self::collect(&env, &admin, amount);
+
+        WaqfYieldRecorded {
+            campaign_id,
+            amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the returns recorded and paid out for a waqf campaign
+    pub fn get_waqf_yield(env: Env, campaign_id: u32) -> Option<WaqfYield> {
+        let funds: Map<u32, WaqfYield> = env.storage()
+            .instance()
+            .get(&WAQF)
+            .unwrap_or(Map::new(&env));
+
+        funds.get(campaign_id)
+    }
+
+    // Turn a UMKM campaign into a qard hasan loan (only admin). The
+    // recipient becomes the borrower when the funds are withdrawn.
+    pub fn set_loan_terms(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        mode: RepaymentMode,
+        installments: u32,
+        interval: u64,
+    ) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set loan terms");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.category != CampaignCategory::UMKM {
+            panic!("Loans are only for UMKM campaigns");
+        }
+        if installments == 0 || interval == 0 {
+            panic!("Invalid repayment schedule");
+        }
+        if Self::get_loan(env.clone(), campaign_id).is_some() {
+            panic!("Loan already disbursed");
+        }
+        Self::set_mode(&env, campaign_id, CampaignMode::Loan);
+
+        let mut all_terms: Map<u32, LoanTerms> = env.storage()
+            .instance()
+            .get(&LOAN_TERMS)
+            .unwrap_or(Map::new(&env));
+        all_terms.set(
+            campaign_id,
+            LoanTerms {
+                mode,
+                installments,
+                interval,
+            },
+        );
+        env.storage().instance().set(&LOAN_TERMS, &all_terms);
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn get_loan_terms(env: Env, campaign_id: u32) -> Option<LoanTerms> {
+        let all_terms: Map<u32, LoanTerms> = env.storage()
+            .instance()
+            .get(&LOAN_TERMS)
+            .unwrap_or(Map::new(&env));
+
+        all_terms.get(campaign_id)
+    }
+
+    // Record a loan installment and pass it on according to the campaign's
+    // repayment mode. Returns the amount still outstanding.
+    pub fn repay(env: Env, borrower: Address, campaign_id: u32, amount: i128) -> i128 {
+        borrower.require_auth();
+
+        let mut loans: Map<u32, Loan> = env.storage()
+            .instance()
+            .get(&LOANS)
+            .unwrap_or(Map::new(&env));
+        let mut loan = loans
+            .get(campaign_id)
+            .unwrap_or_else(|| panic!("No loan for campaign"));
+        if borrower != loan.borrower {
+            panic!("Only borrower can repay");
+        }
+
+        donation_core::validate_amount(&env, amount);
+        let outstanding = loan.principal - loan.repaid;
+        if amount > outstanding {
+            panic!("Amount exceeds outstanding loan");
+        }
+
+        loan.repaid += amount;
+        let mode = loan.terms.mode.clone();
+        loans.set(campaign_id, loan);
+        env.storage().instance().set(&LOANS, &loans);
+
+        Self::collect(&env, &borrower, amount);
+
+        // Funds the loan drew from the pool or other campaigns go back to
+        // the pool, only the donated part is returned to donors
+        let to_pool = match mode {
+            RepaymentMode::Revolving => amount,
+            RepaymentMode::ReturnToDonors => {
+                amount - Self::return_to_donors(&env, campaign_id, amount)
+            }
+        };
+        if to_pool > 0 {
+            // Recorded under the contract so repayments are not counted as gifts
+            let contract = env.current_contract_address();
+            Self::deposit_to_pool(&env, CampaignCategory::UMKM, &contract, to_pool);
+        }
+
+        LoanRepaid {
+            campaign_id,
+            borrower,
+            amount,
+            outstanding: outstanding - amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        outstanding - amount
+    }
+
+    pub fn get_loan(env: Env, campaign_id: u32) -> Option<Loan> {
+        let loans: Map<u32, Loan> = env.storage()
+            .instance()
+            .get(&LOANS)
+            .unwrap_or(Map::new(&env));
+
+        loans.get(campaign_id)
+    }
+
+    // Outstanding balance and schedule position of a loan. Installments
+    // are equal, the last one taking any remainder.
+    pub fn get_loan_status(env: Env, campaign_id: u32) -> LoanStatus {
+        let loan = Self::get_loan(env.clone(), campaign_id)
+            .unwrap_or_else(|| panic!("No loan for campaign"));
+
+        let count = loan.terms.installments as u64;
+        let elapsed = env.ledger().timestamp().saturating_sub(loan.disbursed_at);
+        let passed = (elapsed / loan.terms.interval).min(count);
+
+        let n = count as i128;
+        let p = passed as i128;
+        let due = loan.principal / n * p + loan.principal % n * p / n;
+
+        let next_due_at = if passed < count {
+            loan.disbursed_at
+                .saturating_add(loan.terms.interval.saturating_mul(passed + 1))
+        } else {
+            0
+        };
+
+        LoanStatus {
+            outstanding: loan.principal - loan.repaid,
+            due,
+            next_due_at,
+            is_overdue: loan.repaid < due,
+        }
+    }
+
+    // Set who attests enrollment for a scholarship campaign (only admin)
+    pub fn set_scholarship_verifier(env: Env, admin: Address, campaign_id: u32, verifier: Address) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set verifier");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.category != CampaignCategory::Pendidikan {
+            panic!("Scholarship schedules are only for Pendidikan campaigns");
+        }
+
+        let mut verifiers: Map<u32, Address> = env.storage()
+            .instance()
+            .get(&VERIFIERS)
+            .unwrap_or(Map::new(&env));
+        verifiers.set(campaign_id, verifier);
+        env.storage().instance().set(&VERIFIERS, &verifiers);
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn get_scholarship_verifier(env: Env, campaign_id: u32) -> Option<Address> {
+        let verifiers: Map<u32, Address> = env.storage()
+            .instance()
+            .get(&VERIFIERS)
+            .unwrap_or(Map::new(&env));
+
+        verifiers.get(campaign_id)
+    }
+
+    // Schedule a scholarship payout for a beneficiary (only admin). Once a
+    // campaign has a schedule its funds only leave through claims.
+    pub fn add_installment(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        beneficiary: Address,
+        amount: i128,
+        due_at: u64,
+    ) -> u32 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can schedule installments");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.category != CampaignCategory::Pendidikan {
+            panic!("Scholarship schedules are only for Pendidikan campaigns");
+        }
+        donation_core::validate_amount(&env, amount);
+        Self::set_mode(&env, campaign_id, CampaignMode::Scholarship);
+
+        let mut schedules: Map<u32, Vec<Installment>> = env.storage()
+            .instance()
+            .get(&SCHEDULES)
+            .unwrap_or(Map::new(&env));
+        let mut installments = schedules.get(campaign_id).unwrap_or(Vec::new(&env));
+
+        let installment_id = installments.len();
+        installments.push_back(Installment {
+            id: installment_id,
+            beneficiary,
+            amount,
+            due_at,
+            attested: false,
+            claimed: false,
+        });
+        schedules.set(campaign_id, installments);
+        env.storage().instance().set(&SCHEDULES, &schedules);
+
+        extend_instance_ttl(&env);
+
+        installment_id
+    }
+
+    // Confirm continued enrollment for an installment (only the campaign's verifier)
+    pub fn attest_enrollment(env: Env, verifier: Address, campaign_id: u32, installment_id: u32) {
+        verifier.require_auth();
+
+        if Self::get_scholarship_verifier(env.clone(), campaign_id) != Some(verifier) {
+            panic!("Only verifier can attest enrollment");
+        }
+
+        let mut schedules: Map<u32, Vec<Installment>> = env.storage()
+            .instance()
+            .get(&SCHEDULES)
+            .unwrap_or(Map::new(&env));
+        let mut installments = schedules.get(campaign_id).unwrap_or(Vec::new(&env));
+        let mut installment = installments
+            .get(installment_id)
+            .unwrap_or_else(|| panic!("Installment not found"));
+
+        installment.attested = true;
+        installments.set(installment_id, installment);
+        schedules.set(campaign_id, installments);
+        env.storage().instance().set(&SCHEDULES, &schedules);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Pay out a due, attested installment to its beneficiary
+    pub fn claim_installment(env: Env, beneficiary: Address, campaign_id: u32, installment_id: u32) -> i128 {
+        beneficiary.require_auth();
+
+        let mut schedules: Map<u32, Vec<Installment>> = env.storage()
+            .instance()
+            .get(&SCHEDULES)
+            .unwrap_or(Map::new(&env));
+        let mut installments = schedules.get(campaign_id).unwrap_or(Vec::new(&env));
+        let mut installment = installments
+            .get(installment_id)
+            .unwrap_or_else(|| panic!("Installment not found"));
+
+        if beneficiary != installment.beneficiary {
+            panic!("Only beneficiary can claim");
+        }
+        if installment.claimed {
+            panic!("Installment already claimed");
+        }
+        if env.ledger().timestamp() < installment.due_at {
+            panic!("Installment is not due yet");
+        }
+        if !installment.attested {
+            panic!("Enrollment not attested");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        campaign.withdraw(&env, installment.amount);
+        save_campaign(&env, &campaign);
+
+        let amount = installment.amount;
+        installment.claimed = true;
+        installments.set(installment_id, installment);
+        schedules.set(campaign_id, installments);
+        env.storage().instance().set(&SCHEDULES, &schedules);
+
+        Self::pay_out(&env, &beneficiary, amount);
+        Self::record_payout(&env, campaign_id, amount);
+
+        InstallmentClaimed {
+            campaign_id,
+            installment_id,
+            beneficiary,
+            amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        amount
+    }
+
+    // Get the scholarship schedule of a campaign
+    pub fn get_installments(env: Env, campaign_id: u32) -> Vec<Installment> {
+        let schedules: Map<u32, Vec<Installment>> = env.storage()
+            .instance()
+            .get(&SCHEDULES)
+            .unwrap_or(Map::new(&env));
+
+        schedules.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Approve or remove a vendor that campaigns may pay directly (only admin)
+    pub fn set_vendor(env: Env, admin: Address, vendor: Address, approved: bool) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can manage vendors");
+        }
+
+        let mut vendors: Map<Address, bool> = env.storage()
+            .instance()
+            .get(&VENDORS)
+            .unwrap_or(Map::new(&env));
+        if approved {
+            vendors.set(vendor, true);
+        } else {
+            vendors.remove(vendor);
+        }
+        env.storage().instance().set(&VENDORS, &vendors);
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn is_vendor(env: Env, vendor: Address) -> bool {
+        let vendors: Map<Address, bool> = env.storage()
+            .instance()
+            .get(&VENDORS)
+            .unwrap_or(Map::new(&env));
+
+        vendors.contains_key(vendor)
+    }
+
+    // Mark a Kesehatan campaign as paying only approved vendors (only admin)
+    pub fn set_vendor_only(env: Env, admin: Address, campaign_id: u32, vendor_only: bool) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set vendor-only");
+        }
+
+        let campaign = load_campaign(&env, campaign_id);
+        if campaign.category != CampaignCategory::Kesehatan {
+            panic!("Vendor payments are only for Kesehatan campaigns");
+        }
+
+        if vendor_only {
+            Self::set_mode(&env, campaign_id, CampaignMode::VendorOnly);
+        } else if Self::is_vendor_only(env.clone(), campaign_id) {
+            Self::clear_mode(&env, campaign_id);
+        }
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn is_vendor_only(env: Env, campaign_id: u32) -> bool {
+        Self::get_campaign_mode(env, campaign_id) == Some(CampaignMode::VendorOnly)
+    }
+
+    // Pay an approved vendor's invoice from a vendor-only campaign (only recipient)
+    pub fn pay_vendor(
+        env: Env,
+        recipient: Address,
+        campaign_id: u32,
+        vendor: Address,
+        amount: i128,
+        invoice_hash: BytesN<32>,
+    ) {
+        recipient.require_auth();
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        if recipient != campaign.recipient {
+            panic!("Only recipient can pay vendors");
+        }
+        if !Self::is_vendor_only(env.clone(), campaign_id) {
+            panic!("Campaign is not vendor-only");
+        }
+        if !Self::is_vendor(env.clone(), vendor.clone()) {
+            panic!("Vendor is not approved");
+        }
+        match campaign.status {
+            CampaignStatus::Active | CampaignStatus::Completed | CampaignStatus::Closed => {}
+            _ => panic!("Campaign is not open for payouts"),
+        }
+
+        // An invoice is paid once across all campaigns
+        let invoice_key = (INVOICES, invoice_hash.clone());
+        if env.storage().persistent().has(&invoice_key) {
+            panic!("Invoice already paid");
+        }
+        env.storage().persistent().set(&invoice_key, &campaign_id);
+        extend_persistent_ttl(&env, &invoice_key);
+
+        campaign.withdraw(&env, amount);
+        save_campaign(&env, &campaign);
+
+        let mut all_payments: Map<u32, Vec<VendorPayment>> = env.storage()
+            .instance()
+            .get(&VENDOR_PAYMENTS)
+            .unwrap_or(Map::new(&env));
+        let mut payments = all_payments.get(campaign_id).unwrap_or(Vec::new(&env));
+
+        payments.push_back(VendorPayment {
+            campaign_id,
+            vendor: vendor.clone(),
+            amount,
+            invoice_hash: invoice_hash.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        all_payments.set(campaign_id, payments);
+        env.storage().instance().set(&VENDOR_PAYMENTS, &all_payments);
+
+        Self::pay_out(&env, &vendor, amount);
+        Self::record_payout(&env, campaign_id, amount);
+
+        VendorPaid {
+            campaign_id,
+            vendor,
+            amount,
+            invoice_hash,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the vendor payments made by a campaign
+    pub fn get_vendor_payments(env: Env, campaign_id: u32) -> Vec<VendorPayment> {
+        let all_payments: Map<u32, Vec<VendorPayment>> = env.storage()
+            .instance()
+            .get(&VENDOR_PAYMENTS)
+            .unwrap_or(Map::new(&env));
+
+        all_payments.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Set how long recipients have to report after a withdrawal (only admin)
+    pub fn set_report_deadline(env: Env, admin: Address, deadline: u64) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set report deadline");
+        }
+        if deadline == 0 {
+            panic!("Report deadline must be positive");
+        }
+
+        env.storage().instance().set(&REPORT_DEADLINE, &deadline);
+
+        extend_instance_ttl(&env);
+    }
+
+    pub fn get_report_deadline(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&REPORT_DEADLINE)
+            .unwrap_or(DEFAULT_REPORT_DEADLINE)
+    }
+
+    // Report how withdrawn funds were spent (only recipient)
+    pub fn submit_report(
+        env: Env,
+        recipient: Address,
+        campaign_id: u32,
+        amount_spent: i128,
+        description: String,
+        doc_hash: BytesN<32>,
+    ) {
+        recipient.require_auth();
+
+        let campaign = load_campaign(&env, campaign_id);
+        if recipient != campaign.recipient {
+            panic!("Only recipient can submit reports");
+        }
+        let paid_out = Self::get_payouts(&env, campaign_id)
+            .unwrap_or_else(|| panic!("Campaign has no withdrawals to report"))
+            .total;
+        donation_core::validate_amount(&env, amount_spent);
+
+        let mut all_reports: Map<u32, Vec<SpendingReport>> = env.storage()
+            .instance()
+            .get(&REPORTS)
+            .unwrap_or(Map::new(&env));
+        let mut reports = all_reports.get(campaign_id).unwrap_or(Vec::new(&env));
+
+        let mut reported = amount_spent;
+        for report in reports.iter() {
+            reported = checked_add(&env, reported, report.amount_spent);
+        }
+        if reported > paid_out {
+            panic!("Reported spending exceeds withdrawn amount");
+        }
+
+        reports.push_back(SpendingReport {
+            campaign_id,
+            amount_spent,
+            description,
+            doc_hash: doc_hash.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+        all_reports.set(campaign_id, reports);
+        env.storage().instance().set(&REPORTS, &all_reports);
+
+        ReportSubmitted {
+            campaign_id,
+            amount_spent,
+            doc_hash,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the spending reports of a campaign
+    pub fn get_reports(env: Env, campaign_id: u32) -> Vec<SpendingReport> {
+        let all_reports: Map<u32, Vec<SpendingReport>> = env.storage()
+            .instance()
+            .get(&REPORTS)
+            .unwrap_or(Map::new(&env));
+
+        all_reports.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Compare reported spending with the amount paid out
+    pub fn get_spending_summary(env: Env, campaign_id: u32) -> SpendingSummary {
+        load_campaign(&env, campaign_id);
+
+        let mut reported_amount = 0i128;
+        let mut last_report_at = 0u64;
+        for report in Self::get_reports(env.clone(), campaign_id).iter() {
+            reported_amount = checked_add(&env, reported_amount, report.amount_spent);
+            last_report_at = report.timestamp;
+        }
+
+        let payouts = Self::get_payouts(&env, campaign_id);
+        let withdrawn_amount = payouts.as_ref().map_or(0, |p| p.total);
+        let last_withdrawal_at = payouts.as_ref().map_or(0, |p| p.last_at);
+
+        let deadline = last_withdrawal_at.saturating_add(Self::get_report_deadline(env.clone()));
+        let is_overdue = payouts.is_some()
+            && (last_report_at < last_withdrawal_at || reported_amount == 0)
+            && env.ledger().timestamp() > deadline;
+
+        SpendingSummary {
+            campaign_id,
+            withdrawn_amount,
+            reported_amount,
+            unreported_amount: withdrawn_amount - reported_amount,
+            last_withdrawal_at,
+            last_report_at,
+            is_overdue,
+        }
+    }
+
+    // Get campaigns whose spending report is past the deadline
+    pub fn get_overdue_reports(env: Env) -> Vec<u32> {
+        let payouts: Map<u32, PayoutRecord> = env.storage()
+            .instance()
+            .get(&PAYOUTS)
+            .unwrap_or(Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        for (campaign_id, _) in payouts.iter() {
+            if Self::get_spending_summary(env.clone(), campaign_id).is_overdue {
+                result.push_back(campaign_id);
+            }
+        }
+
+        result
+    }
+
+    // Get the donations recorded under one batch ID
+    pub fn get_batch_donations(env: Env, batch_id: u32) -> Vec<Donation> {
+        let refs: Vec<u32> = env.storage()
+            .persistent()
+            .get(&(BATCH_INDEX, batch_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for donation_id in refs.iter() {
+            result.push_back(Self::load_donation(&env, donation_id));
+        }
+
+        result
+    }
+
+    // Get all campaigns
+    pub fn get_campaigns(env: Env) -> Vec<Campaign> {
+        let campaigns = load_campaigns(&env);
+
+        let mut result = Vec::new(&env);
+        let count = donation_core::campaign_count(&env);
+
+        for i in 1..=count {
+            if let Some(campaign) = campaigns.get(i) {
+                result.push_back(campaign);
+            }
+        }
+
+        result
+    }
+
+    // Get campaigns of a category
+    pub fn get_campaigns_by_category(env: Env, category: CampaignCategory) -> Vec<Campaign> {
+        let category_index: Map<CampaignCategory, Vec<u32>> = env.storage()
+            .instance()
+            .get(&CATEGORY_INDEX)
+            .unwrap_or(Map::new(&env));
+
+        Self::campaigns_by_ids(&env, category_index.get(category).unwrap_or(Vec::new(&env)))
+    }
+
+    // Get campaigns with a status
+    pub fn get_campaigns_by_status(env: Env, status: CampaignStatus) -> Vec<Campaign> {
+        let status_index: Map<CampaignStatus, Vec<u32>> = env.storage()
+            .instance()
+            .get(&STATUS_INDEX)
+            .unwrap_or(Map::new(&env));
+
+        Self::campaigns_by_ids(&env, status_index.get(status).unwrap_or(Vec::new(&env)))
+    }
+
+    // Get campaigns of a recipient
+    pub fn get_campaigns_by_recipient(env: Env, recipient: Address) -> Vec<Campaign> {
+        let recipient_index: Map<Address, Vec<u32>> = env.storage()
+            .instance()
+            .get(&RECIPIENT_INDEX)
+            .unwrap_or(Map::new(&env));
+
+        Self::campaigns_by_ids(&env, recipient_index.get(recipient).unwrap_or(Vec::new(&env)))
+    }
+
+    // Set a campaign's region code (only admin)
+    pub fn set_region(env: Env, admin: Address, campaign_id: u32, region: String) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the region");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        campaign.region = region;
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get active campaigns in a region
+    pub fn get_active_campaigns_by_region(env: Env, region: String) -> Vec<Campaign> {
+        let mut result = Vec::new(&env);
+        for (_, campaign) in load_campaigns(&env).iter() {
+            if campaign.region == region && campaign.status == CampaignStatus::Active {
+                result.push_back(campaign);
+            }
+        }
+
+        result
+    }
+
+    // Get number of campaigns per category with a status
+    pub fn get_category_counts(env: Env, status: CampaignStatus) -> Vec<CategoryCount> {
+        let counts: Map<(CampaignCategory, CampaignStatus), u32> = env.storage()
+            .instance()
+            .get(&CATEGORY_COUNTS)
+            .unwrap_or(Map::new(&env));
+
+        let mut result = Vec::new(&env);
+        for category in CATEGORIES {
+            let count = counts.get((category.clone(), status.clone())).unwrap_or(0);
+            result.push_back(CategoryCount { category, count });
+        }
+
+        result
+    }
+
+    // Get campaign by ID
+    pub fn get_campaign(env: Env, campaign_id: u32) -> Campaign {
+        load_campaign(&env, campaign_id)
+    }
+
+    // Get a page of donations for campaign
+    pub fn get_campaign_donations(env: Env, campaign_id: u32, start: u32, limit: u32) -> Vec<Donation> {
+        let refs: Vec<u32> = env.storage()
+            .persistent()
+            .get(&(CAMPAIGN_INDEX, campaign_id))
+            .unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for donation_id in refs.iter().skip(start as usize).take(limit as usize) {
+            result.push_back(Self::load_donation(&env, donation_id));
+        }
+
+        result
+    }
+
+    // Get a page of all donations (for admin)
+    pub fn get_all_donations(env: Env, start: u32, limit: u32) -> Vec<Donation> {
+        let count: u32 = env.storage().instance().get(&DONATION_COUNT).unwrap_or(0);
+        let end = start.saturating_add(limit).min(count);
+
+        let mut result = Vec::new(&env);
+        for donation_id in start..end {
+            result.push_back(Self::load_donation(&env, donation_id));
+        }
+
+        result
+    }
+
+    // Get public donations of a donor (anonymous donations are hidden)
+    pub fn get_donor_donations(env: Env, donor: Address, start: u32, limit: u32) -> Vec<Donation> {
+        Self::donor_donations(&env, &donor, false, start, limit)
+    }
+
+    // Get all donations of a donor, including anonymous ones (only the donor)
+    pub fn get_my_donations(env: Env, donor: Address, start: u32, limit: u32) -> Vec<Donation> {
+        donor.require_auth();
+
+        Self::donor_donations(&env, &donor, true, start, limit)
+    }
+
+    // Get total of public donations made by a donor
+    pub fn get_donor_total(env: Env, donor: Address) -> i128 {
+        let donations = Self::donor_donations(&env, &donor, false, 0, u32::MAX);
+
+        let mut total = 0i128;
+        for donation in donations.iter() {
+            total = checked_add(&env, total, donation.amount);
+        }
+
+        // Net out what was handed back
+        for entry in Self::donor_returns(&env, &donor).iter() {
+            if !entry.is_anonymous {
+                total -= entry.amount;
+            }
+        }
+
+        total
+    }
+
+    // Get yearly giving statement of a donor (only the donor)
+    pub fn get_annual_statement(
+        env: Env,
+        donor: Address,
+        year_start_ts: u64,
+        year_end_ts: u64,
+    ) -> AnnualStatement {
+        donor.require_auth();
+
+        if year_end_ts < year_start_ts {
+            panic!("Invalid statement period");
+        }
+
+        let campaigns = load_campaigns(&env);
+
+        let mut category_amounts = [0i128; 5];
+        let mut by_campaign: Vec<CampaignTotal> = Vec::new(&env);
+        let mut total_amount = 0i128;
+        let mut donation_count = 0u32;
+
+        let donations = Self::donor_donations(&env, &donor, true, 0, u32::MAX);
+        for donation in donations.iter() {
+            if donation.timestamp < year_start_ts || donation.timestamp > year_end_ts {
+                continue;
+            }
+
+            let category = campaigns.get(donation.campaign_id).unwrap().category;
+            let slot = CATEGORIES.iter().position(|c| *c == category).unwrap();
+            category_amounts[slot] = checked_add(&env, category_amounts[slot], donation.amount);
+            total_amount = checked_add(&env, total_amount, donation.amount);
+            donation_count += 1;
+
+            // Add to existing campaign entry or start a new one
+            let existing = by_campaign
+                .iter()
+                .position(|entry| entry.campaign_id == donation.campaign_id);
+            match existing {
+                Some(i) => {
+                    let mut entry = by_campaign.get(i as u32).unwrap();
+                    entry.amount = checked_add(&env, entry.amount, donation.amount);
+                    entry.donation_count += 1;
+                    by_campaign.set(i as u32, entry);
+                }
+                None => by_campaign.push_back(CampaignTotal {
+                    campaign_id: donation.campaign_id,
+                    category,
+                    amount: donation.amount,
+                    donation_count: 1,
+                }),
+            }
+        }
+
+        // Gifts to a category pool count towards the category, not a campaign
+        let pool_refs: Vec<(CampaignCategory, u32)> = env.storage()
+            .persistent()
+            .get(&(POOL_DONOR_INDEX, donor.clone()))
+            .unwrap_or(Vec::new(&env));
+        for (category, index) in pool_refs.iter() {
+            let donation = Self::load_pool_donation(&env, &category, index);
+            if donation.timestamp < year_start_ts || donation.timestamp > year_end_ts {
+                continue;
+            }
+
+            let slot = CATEGORIES.iter().position(|c| *c == category).unwrap();
+            category_amounts[slot] = checked_add(&env, category_amounts[slot], donation.amount);
+            total_amount = checked_add(&env, total_amount, donation.amount);
+            donation_count += 1;
+        }
+
+        // Donations handed back in the period reduce what was given
+        for entry in Self::donor_returns(&env, &donor).iter() {
+            if entry.timestamp < year_start_ts || entry.timestamp > year_end_ts {
+                continue;
+            }
+
+            let category = campaigns.get(entry.campaign_id).unwrap().category;
+            let slot = CATEGORIES.iter().position(|c| *c == category).unwrap();
+            category_amounts[slot] -= entry.amount;
+            total_amount -= entry.amount;
+
+            let existing = by_campaign
+                .iter()
+                .position(|e| e.campaign_id == entry.campaign_id);
+            match existing {
+                Some(i) => {
+                    let mut campaign_entry = by_campaign.get(i as u32).unwrap();
+                    campaign_entry.amount -= entry.amount;
+                    by_campaign.set(i as u32, campaign_entry);
+                }
+                None => by_campaign.push_back(CampaignTotal {
+                    campaign_id: entry.campaign_id,
+                    category,
+                    amount: -entry.amount,
+                    donation_count: 0,
+                }),
+            }
+        }
+
+        let mut by_category = Vec::new(&env);
+        for (category, amount) in CATEGORIES.into_iter().zip(category_amounts) {
+            by_category.push_back(CategoryTotal { category, amount });
+        }
+
+        AnnualStatement {
+            donor,
+            year_start: year_start_ts,
+            year_end: year_end_ts,
+            total_amount,
+            donation_count,
+            by_category,
+            by_campaign,
+        }
+    }
+
+    // Close campaign (only admin)
+    pub fn close_campaign(env: Env, admin: Address, campaign_id: u32) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can close campaigns");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        let previous_status = campaign.status.clone();
+        campaign.transition(CampaignStatus::Closed);
+        Self::index_status(&env, &campaign, Some(previous_status));
+
+        // Unfilled qurban units are merged or refunded
+        Self::settle_qurban(&env, &mut campaign);
+
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Set per-donation minimum and maximum for a campaign (0 = no limit)
+    pub fn set_donation_limits(
+        env: Env,
+        admin: Address,
+        campaign_id: u32,
+        min_donation: i128,
+        max_donation: i128,
+    ) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set donation limits");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        campaign.set_donation_limits(&env, min_donation, max_donation);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Choose what happens to donations past the target
+    pub fn set_excess_policy(env: Env, admin: Address, campaign_id: u32, policy: ExcessPolicy) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set excess policy");
+        }
+
+        // Redirect target must be an existing campaign that takes plain donations
+        if let ExcessPolicy::RedirectExcess(target_campaign_id) = policy {
+            load_campaign(&env, target_campaign_id);
+            Self::require_plain_donations(&env, target_campaign_id);
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        campaign.set_excess_policy(&env, policy);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Donate to a category pool instead of a specific campaign
+    pub fn donate_to_category(env: Env, donor: Address, category: CampaignCategory, amount: i128) {
+        donor.require_auth();
+
+        donation_core::validate_amount(&env, amount);
+
+        Self::deposit_to_pool(&env, category, &donor, amount);
+        Self::collect(&env, &donor, amount);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the unallocated balance of a category pool
+    pub fn get_pool_balance(env: Env, category: CampaignCategory) -> i128 {
+        let pools: Map<CampaignCategory, i128> = env.storage()
+            .instance()
+            .get(&POOLS)
+            .unwrap_or(Map::new(&env));
+
+        pools.get(category).unwrap_or(0)
+    }
+
+    // Get a page of the donations made to a category pool
+    pub fn get_pool_donations(
+        env: Env,
+        category: CampaignCategory,
+        start: u32,
+        limit: u32,
+    ) -> Vec<PoolDonation> {
+        let count: u32 = env.storage()
+            .persistent()
+            .get(&(POOL_DONATIONS, category.clone()))
+            .unwrap_or(0);
+        let end = start.saturating_add(limit).min(count);
+
+        let mut result = Vec::new(&env);
+        for index in start..end {
+            result.push_back(Self::load_pool_donation(&env, &category, index));
+        }
+
+        result
+    }
+
+    // Move pooled funds into a campaign of the same category. Pool donations
+    // are drawn oldest first so each allocation names its donors. Returns
+    // the amount moved, which a capped campaign may trim.
+    pub fn allocate_from_pool(
+        env: Env,
+        admin: Address,
+        category: CampaignCategory,
+        campaign_id: u32,
+        amount: i128,
+    ) -> i128 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can allocate pooled funds");
+        }
+
+        donation_core::validate_amount(&env, amount);
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        if campaign.category != category {
+            panic!("Campaign is not in this category");
+        }
+        if campaign.status != CampaignStatus::Active {
+            panic!("Campaign is not active");
+        }
+        Self::require_plain_donations(&env, campaign_id);
+
+        // A capped campaign only takes what fits, the rest stays pooled
+        let (amount, _) = campaign.split_excess(&env, amount);
+        if amount == 0 {
+            panic!("Campaign has no room left");
+        }
+
+        let mut pools: Map<CampaignCategory, i128> = env.storage()
+            .instance()
+            .get(&POOLS)
+            .unwrap_or(Map::new(&env));
+        let balance = pools.get(category.clone()).unwrap_or(0);
+        if amount > balance {
+            panic!("Amount exceeds pool balance");
+        }
+        pools.set(category.clone(), balance - amount);
+        env.storage().instance().set(&POOLS, &pools);
+
+        // Draw from the oldest unallocated pool donations, starting after
+        // the ones already used up
+        let count: u32 = env.storage()
+            .persistent()
+            .get(&(POOL_DONATIONS, category.clone()))
+            .unwrap_or(0);
+        let cursor_key = (POOL_CURSOR, category.clone());
+        let mut cursor: u32 = env.storage().persistent().get(&cursor_key).unwrap_or(0);
+        let timestamp = env.ledger().timestamp();
+        let mut sources = Vec::new(&env);
+        let mut remaining = amount;
+        let mut index = cursor;
+        while remaining > 0 && index < count {
+            let mut donation = Self::load_pool_donation(&env, &category, index);
+            let taken = (donation.amount - donation.allocated).min(remaining);
+            donation.allocated += taken;
+            remaining -= taken;
+
+            if donation.allocated == donation.amount {
+                cursor = index + 1;
+            }
+            Self::save_pool_donation(&env, index, &donation);
+
+            // Let the donor trace where this part went
+            let trace_key = (POOL_TRACES, donation.donor.clone());
+            let mut traces: Vec<PoolTrace> = env.storage()
+                .persistent()
+                .get(&trace_key)
+                .unwrap_or(Vec::new(&env));
+            traces.push_back(PoolTrace {
+                category: category.clone(),
+                campaign_id,
+                amount: taken,
+                timestamp,
+            });
+            env.storage().persistent().set(&trace_key, &traces);
+            extend_persistent_ttl(&env, &trace_key);
+
+            sources.push_back(AllocationSource {
+                donor: donation.donor,
+                amount: taken,
+            });
+            index += 1;
+        }
+        env.storage().persistent().set(&cursor_key, &cursor);
+        extend_persistent_ttl(&env, &cursor_key);
+
+        campaign.redirect_in(&env, amount);
+        Self::check_target(&env, &mut campaign);
+        save_campaign(&env, &campaign);
+
+        let allocations_key = (POOL_ALLOCATIONS, category.clone());
+        let allocation_index: u32 = env.storage()
+            .persistent()
+            .get(&allocations_key)
+            .unwrap_or(0);
+        let allocation_key = (POOL_ALLOCATIONS, category.clone(), allocation_index);
+        env.storage().persistent().set(&allocation_key, &PoolAllocation {
+            category: category.clone(),
+            campaign_id,
+            amount,
+            timestamp,
+            sources,
+        });
+        extend_persistent_ttl(&env, &allocation_key);
+        env.storage().persistent().set(&allocations_key, &(allocation_index + 1));
+        extend_persistent_ttl(&env, &allocations_key);
+
+        PoolAllocated {
+            category,
+            campaign_id,
+            amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        amount
+    }
+
+    // Get a page of the allocations made from a category pool
+    pub fn get_pool_allocations(
+        env: Env,
+        category: CampaignCategory,
+        start: u32,
+        limit: u32,
+    ) -> Vec<PoolAllocation> {
+        let count: u32 = env.storage()
+            .persistent()
+            .get(&(POOL_ALLOCATIONS, category.clone()))
+            .unwrap_or(0);
+        let end = start.saturating_add(limit).min(count);
+
+        let mut result = Vec::new(&env);
+        for index in start..end {
+            result.push_back(
+                env.storage()
+                    .persistent()
+                    .get(&(POOL_ALLOCATIONS, category.clone(), index))
+                    .unwrap(),
+            );
+        }
+
+        result
+    }
+
+    // Trace where a donor's pool donations were allocated, a page at a time
+    pub fn trace_pool_donations(env: Env, donor: Address, start: u32, limit: u32) -> Vec<PoolTrace> {
+        let traces: Vec<PoolTrace> = env.storage()
+            .persistent()
+            .get(&(POOL_TRACES, donor))
+            .unwrap_or(Vec::new(&env));
+
+        let mut result = Vec::new(&env);
+        for trace in traces.iter().skip(start as usize).take(limit as usize) {
+            result.push_back(trace);
+        }
+
+        result
+    }
+
+    // Set how long a redirect notice must stand before funds can move
+    pub fn set_redirect_grace_period(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the grace period");
+        }
+        if seconds < MIN_REDIRECT_GRACE {
+            panic!("Grace period is too short");
+        }
+
+        env.storage().instance().set(&REDIRECT_GRACE, &seconds);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the redirect grace period in seconds
+    pub fn get_redirect_grace_period(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&REDIRECT_GRACE)
+            .unwrap_or(DEFAULT_REDIRECT_GRACE)
+    }
+
+    // Notify donors that stranded funds will move to another campaign
+    pub fn announce_redirect(
+        env: Env,
+        admin: Address,
+        from_campaign: u32,
+        to_campaign: u32,
+        amount: i128,
+    ) -> u64 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can redirect funds");
+        }
+
+        let from = load_campaign(&env, from_campaign);
+        let to = load_campaign(&env, to_campaign);
+        Self::check_redirect(&env, &from, &to);
+
+        donation_core::validate_amount(&env, amount);
+        if amount > from.current_amount {
+            panic!("Amount exceeds campaign balance");
+        }
+
+        // A new notice replaces any earlier one for the same campaign
+        let announced_at = env.ledger().timestamp();
+        let executable_at =
+            announced_at.saturating_add(Self::get_redirect_grace_period(env.clone()));
+
+        let mut pending: Map<u32, PendingRedirect> = env.storage()
+            .instance()
+            .get(&PENDING_REDIRECTS)
+            .unwrap_or(Map::new(&env));
+        pending.set(
+            from_campaign,
+            PendingRedirect {
+                to_campaign,
+                amount,
+                announced_at,
+                executable_at,
+            },
+        );
+        env.storage().instance().set(&PENDING_REDIRECTS, &pending);
+
+        RedirectAnnounced {
+            from_campaign,
+            to_campaign,
+            amount,
+            executable_at,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        executable_at
+    }
+
+    // Get the redirect announced for a campaign, if any
+    pub fn get_pending_redirect(env: Env, from_campaign: u32) -> Option<PendingRedirect> {
+        let pending: Map<u32, PendingRedirect> = env.storage()
+            .instance()
+            .get(&PENDING_REDIRECTS)
+            .unwrap_or(Map::new(&env));
+
+        pending.get(from_campaign)
+    }
+
+    // Move announced funds once the grace period has passed, returning the
+    // amount that fit under the target campaign's cap
+    pub fn redirect_funds(
+        env: Env,
+        admin: Address,
+        from_campaign: u32,
+        to_campaign: u32,
+        amount: i128,
+    ) -> i128 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can redirect funds");
+        }
+
+        // Must match the notice donors were given
+        let mut pending: Map<u32, PendingRedirect> = env.storage()
+            .instance()
+            .get(&PENDING_REDIRECTS)
+            .unwrap_or(Map::new(&env));
+        let notice = match pending.get(from_campaign) {
+            Some(notice) if notice.to_campaign == to_campaign && notice.amount == amount => notice,
+            _ => panic!("No matching redirect notice"),
+        };
+        if env.ledger().timestamp() < notice.executable_at {
+            panic!("Grace period has not passed");
+        }
+
+        // Re-check, the campaigns may have changed since the notice
+        let mut from = load_campaign(&env, from_campaign);
+        let mut to = load_campaign(&env, to_campaign);
+        Self::check_redirect(&env, &from, &to);
+
+        // Only what fits under the target's cap moves, the rest stays put
+        let (accepted, _) = to.split_excess(&env, amount);
+        if accepted == 0 {
+            panic!("Target campaign has no room left");
+        }
+
+        from.redirect_out(&env, accepted);
+        to.redirect_in(&env, accepted);
+        Self::check_target(&env, &mut to);
+
+        save_campaign(&env, &from);
+        save_campaign(&env, &to);
+
+        pending.remove(from_campaign);
+        env.storage().instance().set(&PENDING_REDIRECTS, &pending);
+
+        // Record in both campaigns' redirect history
+        let entry = FundRedirect {
+            from_campaign,
+            to_campaign,
+            amount: accepted,
+            announced_at: notice.announced_at,
+            executed_at: env.ledger().timestamp(),
+        };
+        let mut log: Map<u32, Vec<FundRedirect>> = env.storage()
+            .instance()
+            .get(&REDIRECT_LOG)
+            .unwrap_or(Map::new(&env));
+        for campaign_id in [from_campaign, to_campaign] {
+            let mut entries = log.get(campaign_id).unwrap_or(Vec::new(&env));
+            entries.push_back(entry.clone());
+            log.set(campaign_id, entries);
+        }
+        env.storage().instance().set(&REDIRECT_LOG, &log);
+
+        FundsRedirected {
+            from_campaign,
+            to_campaign,
+            amount: accepted,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        accepted
+    }
+
+    // Get redirects into or out of a campaign
+    pub fn get_redirect_history(env: Env, campaign_id: u32) -> Vec<FundRedirect> {
+        let log: Map<u32, Vec<FundRedirect>> = env.storage()
+            .instance()
+            .get(&REDIRECT_LOG)
+            .unwrap_or(Map::new(&env));
+
+        log.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Append a stretch goal, reopening the campaign if it already completed
+    pub fn add_stretch_goal(env: Env, admin: Address, campaign_id: u32, target_amount: i128) -> u32 {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can add stretch goals");
+        }
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        if campaign.goal_type != GoalType::Fixed {
+            panic!("Stretch goals need a fixed target");
+        }
+        Self::require_not_qurban(&env, campaign_id);
+
+        let mut all_goals: Map<u32, Vec<StretchGoal>> = env.storage()
+            .instance()
+            .get(&STRETCH_GOALS)
+            .unwrap_or(Map::new(&env));
+        let mut goals = all_goals.get(campaign_id).unwrap_or(Vec::new(&env));
+
+        // Each stretch goal must go beyond every goal before it
+        let mut highest = campaign.target_amount;
+        if let Some(last) = goals.last() {
+            highest = highest.max(last.target_amount);
+        }
+        donation_core::validate_target(&env, target_amount);
+        if target_amount <= highest {
+            panic!("Stretch target must exceed the current goal");
+        }
+
+        match campaign.status {
+            CampaignStatus::Active => {}
+            CampaignStatus::Completed => {
+                campaign.reopen(&env, target_amount);
+                Self::index_status(&env, &campaign, Some(CampaignStatus::Completed));
+            }
+            _ => panic!("Campaign must be active or completed"),
+        }
+
+        let index = goals.len();
+        goals.push_back(StretchGoal {
+            target_amount,
+            added_at: env.ledger().timestamp(),
+            reached_at: 0,
+        });
+        all_goals.set(campaign_id, goals);
+        env.storage().instance().set(&STRETCH_GOALS, &all_goals);
+
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+
+        index
+    }
+
+    // Get the stretch goals of a campaign in the order they were added
+    pub fn get_stretch_goals(env: Env, campaign_id: u32) -> Vec<StretchGoal> {
+        let all_goals: Map<u32, Vec<StretchGoal>> = env.storage()
+            .instance()
+            .get(&STRETCH_GOALS)
+            .unwrap_or(Map::new(&env));
+
+        all_goals.get(campaign_id).unwrap_or(Vec::new(&env))
+    }
+
+    // Choose between a fixed, flexible or open-ended goal
+    pub fn set_goal_type(env: Env, admin: Address, campaign_id: u32, goal_type: GoalType) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set goal type");
+        }
+
+        Self::require_not_qurban(&env, campaign_id);
+
+        let mut campaign = load_campaign(&env, campaign_id);
+        campaign.set_goal_type(&env, goal_type);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get total donations across all campaigns
+    pub fn get_total_donations(env: Env) -> i128 {
+        let campaigns = load_campaigns(&env);
+
+        let count = donation_core::campaign_count(&env);
+        let mut total = 0i128;
+
+        for i in 1..=count {
+            if let Some(campaign) = campaigns.get(i) {
+                total = checked_add(&env, total, campaign.current_amount);
+            }
+        }
+
+        total
+    }
+
+    // Withdraw funds (only recipient of campaign)
+    pub fn withdraw(env: Env, recipient: Address, campaign_id: u32) -> i128 {
+        recipient.require_auth();
+
+        let mut campaign = load_campaign(&env, campaign_id);
+
+        // Verify recipient
+        if recipient != campaign.recipient {
+            panic!("Only recipient can withdraw");
+        }
+
+        // Waqf principal stays in the campaign, only returns are paid out
+        if Self::is_waqf(&env, campaign_id) {
+            return Self::withdraw_yield(&env, &recipient, campaign_id);
+        }
+        if Self::has_schedule(&env, campaign_id) {
+            panic!("Scholarship funds are paid through claim_installment");
+        }
+        if Self::is_vendor_only(env.clone(), campaign_id) {
+            panic!("Vendor-only campaigns pay through pay_vendor");
+        }
+
+        // Fixed goals pay out once on completion, other goals can
+        // withdraw while open and the remainder after closing. Closed
+        // qurban campaigns pay out the units that were filled.
+        let closes = match campaign.status {
+            CampaignStatus::Completed => true,
+            CampaignStatus::Active | CampaignStatus::Closed
+                if campaign.allows_partial_withdrawal() => false,
+            CampaignStatus::Closed if Self::is_qurban(&env, campaign_id) => false,
+            _ => panic!("Campaign must be completed to withdraw"),
+        };
+
+        if campaign.current_amount <= 0 {
+            panic!("No funds to withdraw");
+        }
+
+        // Move balance to the withdrawn total
+        let amount = campaign.withdraw_all(&env);
+        if closes {
+            campaign.transition(CampaignStatus::Closed);
+            Self::index_status(&env, &campaign, Some(CampaignStatus::Completed));
+        }
+
+        save_campaign(&env, &campaign);
+
+        Self::pay_out(&env, &recipient, amount);
+        Self::record_payout(&env, campaign_id, amount);
+        Self::disburse_loan(&env, campaign_id, &recipient, amount);
+
+        extend_instance_ttl(&env);
+
+        amount
+    }
+
+    // Recompute a campaign's donations and check them against its balance
+    pub fn verify_integrity(env: Env, campaign_id: u32) -> IntegrityReport {
+        let campaign = load_campaign(&env, campaign_id);
+        let donations = Self::get_campaign_donations(env.clone(), campaign_id, 0, u32::MAX);
+
+        let mut total = 0i128;
+        for donation in donations.iter() {
+            total = checked_add(&env, total, donation.amount);
+        }
+
+        // With custody the token balance must also match what is held
+        let mut report = campaign.integrity_report(total);
+        if env.storage().instance().has(&TOKEN) {
+            report.is_consistent &= Self::verify_custody(env).is_consistent;
+        }
+
+        report
+    }
+
+    // Hold donations in a token (only admin, before any funds are held)
+    pub fn set_token(env: Env, admin: Address, token: Address) {
+        admin.require_auth();
+
+        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
+        if admin != stored_admin {
+            panic!("Only admin can set the token");
+        }
+        if env.storage().instance().has(&TOKEN) {
+            panic!("Token already set");
+        }
+        if Self::held_amount(&env) != 0 {
+            panic!("Token must be set before funds are held");
+        }
+
+        env.storage().instance().set(&TOKEN, &token);
+
+        extend_instance_ttl(&env);
+    }
+
+    // Get the custody token, if donations are held on-chain
+    pub fn get_token(env: Env) -> Option<Address> {
+        env.storage().instance().get(&TOKEN)
+    }
+
+    // Compare the contract's token balance with campaign and pool balances
+    pub fn verify_custody(env: Env) -> CustodyReport {
+        let token_address: Address = env.storage()
+            .instance()
+            .get(&TOKEN)
+            .unwrap_or_else(|| panic!("Token not set"));
+
+        let token_balance =
+            token::TokenClient::new(&env, &token_address).balance(&env.current_contract_address());
+        let held_amount = Self::held_amount(&env);
+
+        CustodyReport {
+            token_balance,
+            held_amount,
+            surplus: (token_balance - held_amount).max(0),
+            is_consistent: token_balance >= held_amount,
+        }
+    }
+
+    // Get admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&ADMIN).unwrap()
+    }
+
+    // Whether a campaign can still receive redirected excess
+    fn redirect_target_open(env: &Env, campaign_id: u32) -> bool {
+        load_campaigns(env)
+            .get(campaign_id)
+            .is_some_and(|campaign| campaign.status == CampaignStatus::Active)
+            && Self::takes_plain_donations(env, campaign_id)
+    }
+
+    // Record a donation, following the campaign's excess policy.
+    // Redirected excess skips the receiving campaign's donation limits
+    // and is never redirected a second time; anything left is refunded.
+    fn apply_donation(
+        env: &Env,
+        donor: &Address,
+        campaign_id: u32,
+        amount: i128,
+        is_anonymous: bool,
+        batch_id: u32,
+        redirected: bool,
+    ) -> i128 {
+        // Get campaign
+        let mut campaign = load_campaign(env, campaign_id);
+
+        // Check if campaign is active
+        if campaign.status != CampaignStatus::Active {
+            panic!("Campaign is not active");
+        }
+
+        // Validate against the campaign's donation limits
+        if redirected {
+            donation_core::validate_amount(env, amount);
+        } else {
+            campaign.validate_donation(env, amount);
+        }
+
+        // Keep only what fits under the target when the policy caps it
+        let (accepted, excess) = campaign.split_excess(env, amount);
+
+        // Update campaign amount
+        campaign.add_funds(env, accepted);
+
+        Self::check_target(env, &mut campaign);
+        save_campaign(env, &campaign);
+
+        // A full campaign does not record empty donations
+        if accepted > 0 {
+            Self::record_donation(env, Donation {
+                campaign_id,
+                donor: donor.clone(),
+                amount: accepted,
+                timestamp: env.ledger().timestamp(),
+                is_anonymous,
+                batch_id,
+            });
+
+            // Emit event
+            DonationMade {
+                campaign_id,
+                donor: donor.clone(),
+                amount: accepted,
+            }
+            .publish(env);
+        }
+
+        if excess == 0 {
+            return 0;
+        }
 
-#[contractimpl]
-impl ZakatContract {
-    
-    // Initialize contract with admin
-    pub fn initialize(env: Env, admin: Address) {
-        admin.require_auth();
-        
-        env.storage().instance().set(&ADMIN, &admin);
-        env.storage().instance().set(&CAMPAIGN_COUNT, &0u32);
-    }
+        match campaign.excess_policy {
+            // A target that stopped taking donations gets nothing; refund instead
+            ExcessPolicy::RedirectExcess(target_campaign_id)
+                if !redirected && Self::redirect_target_open(env, target_campaign_id) =>
+            {
+                ExcessRedirected {
+                    campaign_id,
+                    target_campaign_id,
+                    amount: excess,
+                }
+                .publish(env);
 
-    // Create new campaign (only admin)
-    pub fn create_campaign(
-        env: Env,
-        admin: Address,
-        title: String,
-        description: String,
-        category: CampaignCategory,
-        target_amount: i128,
-        recipient: Address,
-    ) -> u32 {
-        admin.require_auth();
+                Self::apply_donation(
+                    env,
+                    donor,
+                    target_campaign_id,
+                    excess,
+                    is_anonymous,
+                    batch_id,
+                    true,
+                )
+            }
+            _ => {
+                ExcessRefunded {
+                    campaign_id,
+                    donor: donor.clone(),
+                    amount: excess,
+                }
+                .publish(env);
 
-        // Verify admin
-        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        if admin != stored_admin {
-            panic!("Only admin can create campaigns");
+                excess
+            }
         }
+    }
 
-        // Validate
-        if target_amount <= 0 {
-            panic!("Target amount must be greater than 0");
+    // Store a donation under its own ID and index it by donor, campaign and batch
+    fn record_donation(env: &Env, donation: Donation) {
+        let donation_id: u32 = env.storage().instance().get(&DONATION_COUNT).unwrap_or(0);
+
+        let donor_key = (DONOR_INDEX, donation.donor.clone());
+        let mut donor_refs: Vec<u32> = env.storage()
+            .persistent()
+            .get(&donor_key)
+            .unwrap_or(Vec::new(env));
+        donor_refs.push_back(donation_id);
+        env.storage().persistent().set(&donor_key, &donor_refs);
+        extend_persistent_ttl(env, &donor_key);
+
+        let campaign_key = (CAMPAIGN_INDEX, donation.campaign_id);
+        let mut campaign_refs: Vec<u32> = env.storage()
+            .persistent()
+            .get(&campaign_key)
+            .unwrap_or(Vec::new(env));
+        campaign_refs.push_back(donation_id);
+        env.storage().persistent().set(&campaign_key, &campaign_refs);
+        extend_persistent_ttl(env, &campaign_key);
+
+        // Donations made outside a batch carry batch ID 0
+        if donation.batch_id != 0 {
+            let batch_key = (BATCH_INDEX, donation.batch_id);
+            let mut batch_refs: Vec<u32> = env.storage()
+                .persistent()
+                .get(&batch_key)
+                .unwrap_or(Vec::new(env));
+            batch_refs.push_back(donation_id);
+            env.storage().persistent().set(&batch_key, &batch_refs);
+            extend_persistent_ttl(env, &batch_key);
         }
 
-        // Get current count and increment
-        let campaign_id: u32 = env.storage()
+        let donation_key = (DONATIONS, donation_id);
+        env.storage().persistent().set(&donation_key, &donation);
+        extend_persistent_ttl(env, &donation_key);
+        env.storage().instance().set(&DONATION_COUNT, &(donation_id + 1));
+    }
+
+    fn load_donation(env: &Env, donation_id: u32) -> Donation {
+        env.storage().persistent().get(&(DONATIONS, donation_id)).unwrap()
+    }
+
+    // Add to a category pool, storing the gift under its index in the pool
+    fn deposit_to_pool(env: &Env, category: CampaignCategory, donor: &Address, amount: i128) {
+        let mut pools: Map<CampaignCategory, i128> = env.storage()
             .instance()
-            .get(&CAMPAIGN_COUNT)
-            .unwrap_or(0);
-        
-        let new_id = campaign_id + 1;
+            .get(&POOLS)
+            .unwrap_or(Map::new(env));
+        let balance = pools.get(category.clone()).unwrap_or(0);
+        pools.set(category.clone(), checked_add(env, balance, amount));
+        env.storage().instance().set(&POOLS, &pools);
 
-        // Create campaign
-        let campaign = Campaign {
-            id: new_id,
-            title,
-            description,
+        let count_key = (POOL_DONATIONS, category.clone());
+        let index: u32 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        Self::save_pool_donation(env, index, &PoolDonation {
+            category: category.clone(),
+            donor: donor.clone(),
+            amount,
+            allocated: 0,
+            timestamp: env.ledger().timestamp(),
+        });
+        env.storage().persistent().set(&count_key, &(index + 1));
+        extend_persistent_ttl(env, &count_key);
+
+        // Index the gift by donor for statements
+        let donor_key = (POOL_DONOR_INDEX, donor.clone());
+        let mut refs: Vec<(CampaignCategory, u32)> = env.storage()
+            .persistent()
+            .get(&donor_key)
+            .unwrap_or(Vec::new(env));
+        refs.push_back((category.clone(), index));
+        env.storage().persistent().set(&donor_key, &refs);
+        extend_persistent_ttl(env, &donor_key);
+
+        PoolDonationMade {
             category,
-            target_amount,
-            current_amount: 0,
-            recipient,
-            status: CampaignStatus::Active,
-            created_at: env.ledger().timestamp(),
-        };
+            donor: donor.clone(),
+            amount,
+        }
+        .publish(env);
+    }
 
-        // Store campaign
-        let key = symbol_short!("CAMP");
-        let mut campaigns: Map<u32, Campaign> = env.storage()
-            .instance()
-            .get(&key)
-            .unwrap_or(Map::new(&env));
-        
-        campaigns.set(new_id, campaign);
-        env.storage().instance().set(&key, &campaigns);
-        env.storage().instance().set(&CAMPAIGN_COUNT, &new_id);
+    fn load_pool_donation(env: &Env, category: &CampaignCategory, index: u32) -> PoolDonation {
+        env.storage()
+            .persistent()
+            .get(&(POOL_DONATIONS, category.clone(), index))
+            .unwrap()
+    }
 
-        new_id
+    fn save_pool_donation(env: &Env, index: u32, donation: &PoolDonation) {
+        let key = (POOL_DONATIONS, donation.category.clone(), index);
+        env.storage().persistent().set(&key, donation);
+        extend_persistent_ttl(env, &key);
     }
 
-    // Donate to campaign
-    pub fn donate(
-        env: Env,
-        donor: Address,
-        campaign_id: u32,
-        amount: i128,
+    // Check every leg, then record them under a new batch ID and collect once
+    fn apply_batch(
+        env: &Env,
+        donor: &Address,
+        donations: &Vec<(u32, i128)>,
         is_anonymous: bool,
-    ) {
-        donor.require_auth();
+    ) -> BatchReceipt {
+        if donations.is_empty() {
+            panic!("Batch is empty");
+        }
 
-        // Validate
-        if amount <= 0 {
-            panic!("Amount must be greater than 0");
+        // Check all targets first
+        let mut total = 0i128;
+        for (campaign_id, amount) in donations.iter() {
+            let campaign = load_campaign(env, campaign_id);
+            if campaign.status != CampaignStatus::Active {
+                panic!("Campaign is not active");
+            }
+            Self::require_plain_donations(env, campaign_id);
+            campaign.validate_donation(env, amount);
+            total = checked_add(env, total, amount);
         }
 
-        // Get campaign
-        let key = symbol_short!("CAMP");
-        let mut campaigns: Map<u32, Campaign> = env.storage()
-            .instance()
-            .get(&key)
-            .unwrap();
+        let batch_id: u32 = env.storage().instance().get(&BATCH_COUNT).unwrap_or(0) + 1;
+        env.storage().instance().set(&BATCH_COUNT, &batch_id);
 
-        let mut campaign = campaigns.get(campaign_id).unwrap();
+        let mut refunded = 0i128;
+        for (campaign_id, amount) in donations.iter() {
+            refunded += Self::apply_donation(
+                env,
+                donor,
+                campaign_id,
+                amount,
+                is_anonymous,
+                batch_id,
+                false,
+            );
+        }
 
-        // Check if campaign is active
-        if campaign.status != CampaignStatus::Active {
-            panic!("Campaign is not active");
+        Self::collect(env, donor, total - refunded);
+
+        BatchReceipt { batch_id, refunded }
+    }
+
+    // Fitrah and qurban campaigns only take donations through their own entry points
+    fn require_plain_donations(env: &Env, campaign_id: u32) {
+        match Self::get_campaign_mode(env.clone(), campaign_id) {
+            Some(CampaignMode::Fitrah) => panic!("Use donate_fitrah for fitrah campaigns"),
+            Some(CampaignMode::Qurban) => panic!("Use join_qurban for qurban campaigns"),
+            _ => {}
         }
+    }
 
-        // Update campaign amount
-        campaign.current_amount += amount;
+    fn takes_plain_donations(env: &Env, campaign_id: u32) -> bool {
+        !matches!(
+            Self::get_campaign_mode(env.clone(), campaign_id),
+            Some(CampaignMode::Fitrah | CampaignMode::Qurban)
+        )
+    }
 
-        // Check if target reached
-        if campaign.current_amount >= campaign.target_amount {
-            campaign.status = CampaignStatus::Completed;
+    // Give a campaign its mode, refusing a switch to a different one
+    fn set_mode(env: &Env, campaign_id: u32, mode: CampaignMode) {
+        let mut modes: Map<u32, CampaignMode> = env.storage()
+            .instance()
+            .get(&MODES)
+            .unwrap_or(Map::new(env));
+        match modes.get(campaign_id) {
+            Some(current) if current != mode => panic!("Campaign already uses another mode"),
+            Some(_) => {}
+            None => {
+                modes.set(campaign_id, mode);
+                env.storage().instance().set(&MODES, &modes);
+            }
         }
+    }
 
-        campaigns.set(campaign_id, campaign);
-        env.storage().instance().set(&key, &campaigns);
+    fn clear_mode(env: &Env, campaign_id: u32) {
+        let mut modes: Map<u32, CampaignMode> = env.storage()
+            .instance()
+            .get(&MODES)
+            .unwrap_or(Map::new(env));
+        modes.remove(campaign_id);
+        env.storage().instance().set(&MODES, &modes);
+    }
 
-        // Store donation
-        let donation = Donation {
+    // Keep a donation handed back to its donor, so totals and statements net it out
+    fn record_return(env: &Env, donor: &Address, campaign_id: u32, amount: i128, is_anonymous: bool) {
+        let mut returns = Self::donor_returns(env, donor);
+        returns.push_back(DonationReturn {
             campaign_id,
-            donor: donor.clone(),
             amount,
-            timestamp: env.ledger().timestamp(),
             is_anonymous,
+            timestamp: env.ledger().timestamp(),
+        });
+        let key = (RETURNS, donor.clone());
+        env.storage().persistent().set(&key, &returns);
+        extend_persistent_ttl(env, &key);
+    }
+
+    // A qurban campaign's target is the total price of its units
+    fn require_not_qurban(env: &Env, campaign_id: u32) {
+        if Self::is_qurban(env, campaign_id) {
+            panic!("Qurban targets follow their units");
+        }
+    }
+
+    // Record a loan campaign's payout as owed by the borrower. Later
+    // payouts of the same campaign add to the principal on the same schedule.
+    fn disburse_loan(env: &Env, campaign_id: u32, borrower: &Address, amount: i128) {
+        let all_terms: Map<u32, LoanTerms> = env.storage()
+            .instance()
+            .get(&LOAN_TERMS)
+            .unwrap_or(Map::new(env));
+        let terms = match all_terms.get(campaign_id) {
+            Some(terms) => terms,
+            None => return,
         };
 
-        let donation_key = symbol_short!("DONS");
-        let mut donations: Vec<Donation> = env.storage()
+        let mut loans: Map<u32, Loan> = env.storage()
             .instance()
-            .get(&donation_key)
-            .unwrap_or(Vec::new(&env));
-        
-        donations.push_back(donation);
-        env.storage().instance().set(&donation_key, &donations);
+            .get(&LOANS)
+            .unwrap_or(Map::new(env));
+        let mut loan = loans.get(campaign_id).unwrap_or(Loan {
+            campaign_id,
+            borrower: borrower.clone(),
+            principal: 0,
+            repaid: 0,
+            disbursed_at: env.ledger().timestamp(),
+            terms,
+        });
+        loan.principal = checked_add(env, loan.principal, amount);
+        loans.set(campaign_id, loan);
+        env.storage().instance().set(&LOANS, &loans);
 
-        // Emit event
-        env.events().publish(
-            (symbol_short!("donate"), campaign_id),
-            (donor, amount)
-        );
+        LoanDisbursed {
+            campaign_id,
+            borrower: borrower.clone(),
+            amount,
+        }
+        .publish(env);
     }
 
-    // Get all campaigns
-    pub fn get_campaigns(env: Env) -> Vec<Campaign> {
-        let key = symbol_short!("CAMP");
-        let campaigns: Map<u32, Campaign> = env.storage()
-            .instance()
-            .get(&key)
-            .unwrap_or(Map::new(&env));
+    // Pay the donated share of a repayment back to the campaign's donors by
+    // their share of the donations and return how much was paid. The share
+    // follows donations against funds moved in. Public and anonymous gifts
+    // of a donor are returned separately, so each nets out where it was
+    // counted. Rounding dust goes to the largest donor, first on a tie.
+    fn return_to_donors(env: &Env, campaign_id: u32, amount: i128) -> i128 {
+        let mut totals: Map<(Address, bool), i128> = Map::new(env);
+        let mut donors: Vec<(Address, bool)> = Vec::new(env);
+        let mut total = 0i128;
+        for donation in Self::get_campaign_donations(env.clone(), campaign_id, 0, u32::MAX).iter() {
+            let key = (donation.donor.clone(), donation.is_anonymous);
+            let given = totals.get(key.clone()).unwrap_or_else(|| {
+                donors.push_back(key.clone());
+                0
+            });
+            totals.set(key, checked_add(env, given, donation.amount));
+            total = checked_add(env, total, donation.amount);
+        }
+        if total == 0 {
+            return 0;
+        }
 
-        let mut result = Vec::new(&env);
-        let count: u32 = env.storage().instance().get(&CAMPAIGN_COUNT).unwrap_or(0);
+        let redirected_in = load_campaign(env, campaign_id).redirected_in;
+        let amount = amount
+            .checked_mul(total)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+            / checked_add(env, total, redirected_in);
 
-        for i in 1..=count {
-            if let Some(campaign) = campaigns.get(i) {
-                result.push_back(campaign);
+        let mut largest = donors.get(0).unwrap();
+        let mut shares: Map<(Address, bool), i128> = Map::new(env);
+        let mut assigned = 0i128;
+        for donor in donors.iter() {
+            let given = totals.get(donor.clone()).unwrap();
+            if given > totals.get(largest.clone()).unwrap() {
+                largest = donor.clone();
             }
+            let share = amount
+                .checked_mul(given)
+                .unwrap_or_else(|| panic_with_error!(env, Error::Overflow))
+                / total;
+            shares.set(donor, share);
+            assigned += share;
         }
+        let dust = amount - assigned;
+        shares.set(largest.clone(), shares.get(largest).unwrap() + dust);
 
-        result
+        for key in donors.iter() {
+            let share = shares.get(key.clone()).unwrap();
+            if share == 0 {
+                continue;
+            }
+            let (donor, is_anonymous) = key;
+            Self::pay_out(env, &donor, share);
+            Self::record_return(env, &donor, campaign_id, share, is_anonymous);
+        }
+
+        amount
     }
 
-    // Get campaign by ID
-    pub fn get_campaign(env: Env, campaign_id: u32) -> Campaign {
-        let key = symbol_short!("CAMP");
-        let campaigns: Map<u32, Campaign> = env.storage()
-            .instance()
-            .get(&key)
-            .unwrap();
+    fn has_schedule(env: &Env, campaign_id: u32) -> bool {
+        Self::get_campaign_mode(env.clone(), campaign_id) == Some(CampaignMode::Scholarship)
+    }
 
-        campaigns.get(campaign_id).unwrap()
+    fn is_waqf(env: &Env, campaign_id: u32) -> bool {
+        Self::get_campaign_mode(env.clone(), campaign_id) == Some(CampaignMode::Waqf)
     }
 
-    // Get donations for campaign
-    pub fn get_campaign_donations(env: Env, campaign_id: u32) -> Vec<Donation> {
-        let donation_key = symbol_short!("DONS");
-        let all_donations: Vec<Donation> = env.storage()
+    fn withdraw_yield(env: &Env, recipient: &Address, campaign_id: u32) -> i128 {
+        let mut funds: Map<u32, WaqfYield> = env.storage()
             .instance()
-            .get(&donation_key)
-            .unwrap_or(Vec::new(&env));
+            .get(&WAQF)
+            .unwrap();
+        let mut fund = funds.get(campaign_id).unwrap();
 
-        let mut result = Vec::new(&env);
-        
-        for i in 0..all_donations.len() {
-            let donation = all_donations.get(i).unwrap();
-            if donation.campaign_id == campaign_id {
-                result.push_back(donation);
-            }
+        let amount = fund.recorded - fund.withdrawn;
+        if amount <= 0 {
+            panic!("No waqf returns to withdraw");
         }
+        fund.withdrawn = fund.recorded;
+        funds.set(campaign_id, fund);
+        env.storage().instance().set(&WAQF, &funds);
 
-        result
+        Self::pay_out(env, recipient, amount);
+        Self::record_payout(env, campaign_id, amount);
+
+        extend_instance_ttl(env);
+
+        amount
     }
 
-    // Get all donations (for admin)
-    pub fn get_all_donations(env: Env) -> Vec<Donation> {
-        let donation_key = symbol_short!("DONS");
-        env.storage()
+    // Add to a campaign's payout total and start the spending report deadline
+    fn record_payout(env: &Env, campaign_id: u32, amount: i128) {
+        let mut payouts: Map<u32, PayoutRecord> = env.storage()
             .instance()
-            .get(&donation_key)
-            .unwrap_or(Vec::new(&env))
+            .get(&PAYOUTS)
+            .unwrap_or(Map::new(env));
+        let mut record = payouts
+            .get(campaign_id)
+            .unwrap_or(PayoutRecord { total: 0, last_at: 0 });
+
+        record.total = checked_add(env, record.total, amount);
+        record.last_at = env.ledger().timestamp();
+        payouts.set(campaign_id, record);
+        env.storage().instance().set(&PAYOUTS, &payouts);
     }
 
-    // Close campaign (only admin)
-    pub fn close_campaign(env: Env, admin: Address, campaign_id: u32) {
-        admin.require_auth();
+    fn get_payouts(env: &Env, campaign_id: u32) -> Option<PayoutRecord> {
+        let payouts: Map<u32, PayoutRecord> = env.storage()
+            .instance()
+            .get(&PAYOUTS)
+            .unwrap_or(Map::new(env));
+        payouts.get(campaign_id)
+    }
 
-        let stored_admin: Address = env.storage().instance().get(&ADMIN).unwrap();
-        if admin != stored_admin {
-            panic!("Only admin can close campaigns");
-        }
+    fn is_qurban(env: &Env, campaign_id: u32) -> bool {
+        Self::get_campaign_mode(env.clone(), campaign_id) == Some(CampaignMode::Qurban)
+    }
 
-        let key = symbol_short!("CAMP");
-        let mut campaigns: Map<u32, Campaign> = env.storage()
+    // Consolidate unfilled qurban units when a campaign closes. Units with the
+    // same share price are merged, fullest first; participants left in a unit
+    // that still cannot be filled are refunded.
+    fn settle_qurban(env: &Env, campaign: &mut Campaign) {
+        let mut all_units: Map<u32, Vec<QurbanUnit>> = env.storage()
             .instance()
-            .get(&key)
-            .unwrap();
+            .get(&QURBAN)
+            .unwrap_or(Map::new(env));
+        let mut units = match all_units.get(campaign.id) {
+            Some(units) => units,
+            None => return,
+        };
+
+        // Open units with participants, fullest first (lowest id on a tie)
+        let mut order: Vec<u32> = Vec::new(env);
+        for unit in units.iter() {
+            if unit.status != QurbanUnitStatus::Open || unit.participants.is_empty() {
+                continue;
+            }
+            let mut at = order.len();
+            for (pos, id) in order.iter().enumerate() {
+                if unit.participants.len() > units.get(id).unwrap().participants.len() {
+                    at = pos as u32;
+                    break;
+                }
+            }
+            order.insert(at, unit.id);
+        }
+
+        for i in 0..order.len() {
+            let mut head = units.get(order.get(i).unwrap()).unwrap();
+            if head.status != QurbanUnitStatus::Open {
+                continue;
+            }
+            let share = head.price / head.slots as i128;
+
+            // Pull participants from the emptiest units with the same share price
+            let mut j = order.len();
+            while j > i + 1 && head.participants.len() < head.slots {
+                j -= 1;
+                let mut tail = units.get(order.get(j).unwrap()).unwrap();
+                if tail.status != QurbanUnitStatus::Open || tail.price / tail.slots as i128 != share {
+                    continue;
+                }
+                while head.participants.len() < head.slots && !tail.participants.is_empty() {
+                    head.participants.push_back(tail.participants.pop_back().unwrap());
+                }
+                if tail.participants.is_empty() {
+                    tail.status = QurbanUnitStatus::Merged;
+                }
+                units.set(tail.id, tail);
+            }
 
-        let mut campaign = campaigns.get(campaign_id).unwrap();
-        campaign.status = CampaignStatus::Closed;
+            if head.participants.len() == head.slots {
+                head.status = QurbanUnitStatus::Full;
+                QurbanUnitFull {
+                    campaign_id: campaign.id,
+                    unit_id: head.id,
+                }
+                .publish(env);
+            } else {
+                head.status = QurbanUnitStatus::Refunded;
+                for donor in head.participants.iter() {
+                    // Never hand back more than the campaign still holds
+                    let amount = share.min(campaign.current_amount);
+                    if amount == 0 {
+                        continue;
+                    }
+                    campaign.refund(env, amount);
+                    Self::pay_out(env, &donor, amount);
+                    Self::record_return(env, &donor, campaign.id, amount, false);
+                    QurbanRefunded {
+                        campaign_id: campaign.id,
+                        unit_id: head.id,
+                        donor,
+                        amount,
+                    }
+                    .publish(env);
+                }
+            }
+            units.set(head.id, head);
+        }
 
-        campaigns.set(campaign_id, campaign);
-        env.storage().instance().set(&key, &campaigns);
+        all_units.set(campaign.id, units);
+        env.storage().instance().set(&QURBAN, &all_units);
     }
 
-    // Get total donations across all campaigns
-    pub fn get_total_donations(env: Env) -> i128 {
-        let key = symbol_short!("CAMP");
-        let campaigns: Map<u32, Campaign> = env.storage()
-            .instance()
-            .get(&key)
-            .unwrap_or(Map::new(&env));
+    // Pull tokens from a donor when custody is enabled
+    fn collect(env: &Env, from: &Address, amount: i128) {
+        let token_address: Option<Address> = env.storage().instance().get(&TOKEN);
+        if let Some(token_address) = token_address {
+            if amount > 0 {
+                token::TokenClient::new(env, &token_address).transfer(
+                    from,
+                    env.current_contract_address(),
+                    &amount,
+                );
+            }
+        }
+    }
+
+    // Send tokens to a recipient when custody is enabled
+    fn pay_out(env: &Env, to: &Address, amount: i128) {
+        let token_address: Option<Address> = env.storage().instance().get(&TOKEN);
+        if let Some(token_address) = token_address {
+            if amount > 0 {
+                token::TokenClient::new(env, &token_address).transfer(
+                    &env.current_contract_address(),
+                    to,
+                    &amount,
+                );
+            }
+        }
+    }
 
-        let count: u32 = env.storage().instance().get(&CAMPAIGN_COUNT).unwrap_or(0);
+    // Everything the contract owes: campaign balances plus pooled funds
+    fn held_amount(env: &Env) -> i128 {
         let mut total = 0i128;
+        for (_, campaign) in load_campaigns(env).iter() {
+            total = checked_add(env, total, campaign.current_amount);
+        }
 
-        for i in 1..=count {
-            if let Some(campaign) = campaigns.get(i) {
-                total += campaign.current_amount;
-            }
+        let pools: Map<CampaignCategory, i128> = env.storage()
+            .instance()
+            .get(&POOLS)
+            .unwrap_or(Map::new(env));
+        for (_, balance) in pools.iter() {
+            total = checked_add(env, total, balance);
+        }
+
+        let funds: Map<u32, WaqfYield> = env.storage()
+            .instance()
+            .get(&WAQF)
+            .unwrap_or(Map::new(env));
+        for (_, fund) in funds.iter() {
+            total = checked_add(env, total, fund.recorded - fund.withdrawn);
         }
 
         total
     }
 
-    // Withdraw funds (only recipient of campaign)
-    pub fn withdraw(env: Env, recipient: Address, campaign_id: u32) -> i128 {
-        recipient.require_auth();
+    // Complete an active campaign whose fixed target is reached, moving on
+    // to any pending stretch goal first
+    fn check_target(env: &Env, campaign: &mut Campaign) {
+        if campaign.status != CampaignStatus::Active || !campaign.target_reached() {
+            return;
+        }
+
+        Self::advance_stretch_goals(env, campaign);
+        if campaign.target_reached() {
+            campaign.transition(CampaignStatus::Completed);
+            Self::index_status(env, campaign, Some(CampaignStatus::Active));
+        }
+    }
+
+    // Zakat funds may only move between zakat campaigns
+    fn check_redirect(env: &Env, from: &Campaign, to: &Campaign) {
+        if from.id == to.id {
+            panic_with_error!(env, Error::InvalidRedirect);
+        }
+        if (from.category == CampaignCategory::Zakat) != (to.category == CampaignCategory::Zakat) {
+            panic!("Zakat funds can only move between zakat campaigns");
+        }
+        if Self::is_waqf(env, from.id) {
+            panic!("Waqf principal cannot be redirected");
+        }
+        if Self::has_schedule(env, from.id) {
+            panic!("Scholarship funds stay in escrow");
+        }
+
+        match from.status {
+            CampaignStatus::Completed | CampaignStatus::Closed => {}
+            _ => panic!("Only completed or closed campaigns can be redirected"),
+        }
+        if to.status != CampaignStatus::Active {
+            panic!("Target campaign is not accepting funds");
+        }
+        Self::require_plain_donations(env, to.id);
+    }
 
-        let key = symbol_short!("CAMP");
-        let mut campaigns: Map<u32, Campaign> = env.storage()
+    // Mark stretch goals covered by the balance and raise the target to the next one
+    fn advance_stretch_goals(env: &Env, campaign: &mut Campaign) {
+        let mut all_goals: Map<u32, Vec<StretchGoal>> = env.storage()
             .instance()
-            .get(&key)
-            .unwrap();
+            .get(&STRETCH_GOALS)
+            .unwrap_or(Map::new(env));
+        let mut goals = match all_goals.get(campaign.id) {
+            Some(goals) => goals,
+            None => return,
+        };
 
-        let mut campaign = campaigns.get(campaign_id).unwrap();
+        let mut next_target = None;
+        for i in 0..goals.len() {
+            let mut goal = goals.get(i).unwrap();
+            if goal.reached_at != 0 {
+                continue;
+            }
 
-        // Verify recipient
-        if recipient != campaign.recipient {
-            panic!("Only recipient can withdraw");
+            if campaign.raised_amount() >= goal.target_amount {
+                goal.reached_at = env.ledger().timestamp();
+                goals.set(i, goal.clone());
+
+                StretchGoalReached {
+                    campaign_id: campaign.id,
+                    index: i,
+                    target_amount: goal.target_amount,
+                }
+                .publish(env);
+            } else if next_target.is_none() {
+                next_target = Some(goal.target_amount);
+            }
         }
 
-        // Check if completed
-        if campaign.status != CampaignStatus::Completed {
-            panic!("Campaign must be completed to withdraw");
+        if let Some(target_amount) = next_target {
+            campaign.target_amount = target_amount;
         }
 
-        let amount = campaign.current_amount;
-        
-        if amount <= 0 {
-            panic!("No funds to withdraw");
+        all_goals.set(campaign.id, goals);
+        env.storage().instance().set(&STRETCH_GOALS, &all_goals);
+    }
+
+    // Move a campaign between status index entries and category counts
+    fn index_status(env: &Env, campaign: &Campaign, previous: Option<CampaignStatus>) {
+        let mut status_index: Map<CampaignStatus, Vec<u32>> = env.storage()
+            .instance()
+            .get(&STATUS_INDEX)
+            .unwrap_or(Map::new(env));
+        let mut counts: Map<(CampaignCategory, CampaignStatus), u32> = env.storage()
+            .instance()
+            .get(&CATEGORY_COUNTS)
+            .unwrap_or(Map::new(env));
+
+        if let Some(previous) = previous {
+            if previous == campaign.status {
+                return;
+            }
+
+            let mut ids = status_index.get(previous.clone()).unwrap_or(Vec::new(env));
+            if let Some(i) = ids.first_index_of(campaign.id) {
+                ids.remove(i);
+            }
+            status_index.set(previous.clone(), ids);
+
+            let count_key = (campaign.category.clone(), previous);
+            let count = counts.get(count_key.clone()).unwrap_or(0);
+            counts.set(count_key, count.saturating_sub(1));
         }
 
-        // Reset amount and close campaign
-        campaign.current_amount = 0;
-        campaign.status = CampaignStatus::Closed;
+        let mut ids = status_index.get(campaign.status.clone()).unwrap_or(Vec::new(env));
+        ids.push_back(campaign.id);
+        status_index.set(campaign.status.clone(), ids);
 
-        campaigns.set(campaign_id, campaign);
-        env.storage().instance().set(&key, &campaigns);
+        let count_key = (campaign.category.clone(), campaign.status.clone());
+        let count = counts.get(count_key.clone()).unwrap_or(0);
+        counts.set(count_key, count + 1);
 
-        amount
+        env.storage().instance().set(&STATUS_INDEX, &status_index);
+        env.storage().instance().set(&CATEGORY_COUNTS, &counts);
     }
 
-    // Get admin address
-    pub fn get_admin(env: Env) -> Address {
-        env.storage().instance().get(&ADMIN).unwrap()
+    // Load campaigns for a list of IDs
+    fn campaigns_by_ids(env: &Env, ids: Vec<u32>) -> Vec<Campaign> {
+        let campaigns = load_campaigns(env);
+
+        let mut result = Vec::new(env);
+        for id in ids.iter() {
+            if let Some(campaign) = campaigns.get(id) {
+                result.push_back(campaign);
+            }
+        }
+
+        result
+    }
+
+    fn donor_returns(env: &Env, donor: &Address) -> Vec<DonationReturn> {
+        env.storage()
+            .persistent()
+            .get(&(RETURNS, donor.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    // Collect a page of donations made by a donor using the donor index
+    fn donor_donations(
+        env: &Env,
+        donor: &Address,
+        include_anonymous: bool,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Donation> {
+        let refs: Vec<u32> = env.storage()
+            .persistent()
+            .get(&(DONOR_INDEX, donor.clone()))
+            .unwrap_or(Vec::new(env));
+
+        let mut result = Vec::new(env);
+        let mut skipped = 0u32;
+
+        for donation_id in refs.iter() {
+            if result.len() >= limit {
+                break;
+            }
+
+            let donation = Self::load_donation(env, donation_id);
+            if donation.is_anonymous && !include_anonymous {
+                continue;
+            }
+
+            if skipped < start {
+                skipped += 1;
+                continue;
+            }
+
+            result.push_back(donation);
+        }
+
+        result
     }
 }
 
@@ -1,12 +1,20 @@
 #![cfg(test)]
+extern crate std;
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, EnvTestConfig, Ledger,
+    },
+    token, vec, Address, BytesN, Env, String,
+};
 
 #[test]
 fn test_initialize() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -20,7 +28,7 @@ fn test_initialize() {
 #[test]
 fn test_create_campaign_zakat() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -51,7 +59,7 @@ fn test_create_campaign_zakat() {
 #[test]
 fn test_create_campaign_bencana_alam() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -78,7 +86,7 @@ fn test_create_campaign_bencana_alam() {
 #[test]
 fn test_create_multiple_campaigns() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -128,7 +136,7 @@ fn test_create_multiple_campaigns() {
 #[test]
 fn test_donate_basic() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -159,7 +167,7 @@ fn test_donate_basic() {
 #[test]
 fn test_donate_multiple_donors() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -190,14 +198,14 @@ fn test_donate_multiple_donors() {
     assert_eq!(campaign.current_amount, 90_000_000); // 9 XLM total
     assert_eq!(campaign.status, CampaignStatus::Active); // Not completed yet
 
-    let donations = client.get_campaign_donations(&campaign_id);
+    let donations = client.get_campaign_donations(&campaign_id, &0, &100);
     assert_eq!(donations.len(), 3);
 }
 
 #[test]
 fn test_donate_anonymous() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -220,18 +228,18 @@ fn test_donate_anonymous() {
     // Anonymous donation
     client.donate(&donor, &campaign_id, &30_000_000, &true);
 
-    let donations = client.get_campaign_donations(&campaign_id);
+    let donations = client.get_campaign_donations(&campaign_id, &0, &100);
     assert_eq!(donations.len(), 1);
     
     let donation = donations.get(0).unwrap();
-    assert_eq!(donation.is_anonymous, true);
+    assert!(donation.is_anonymous);
     assert_eq!(donation.amount, 30_000_000);
 }
 
 #[test]
 fn test_campaign_completion() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -262,7 +270,7 @@ fn test_campaign_completion() {
 #[test]
 fn test_campaign_over_target() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -291,52 +299,75 @@ fn test_campaign_over_target() {
 }
 
 #[test]
-fn test_get_all_donations() {
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_campaign_reject_excess() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let donor1 = Address::generate(&env);
-    let donor2 = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
-    // Create 2 campaigns
-    let campaign_id_1 = client.create_campaign(
+    let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Campaign 1"),
-        &String::from_str(&env, "Description 1"),
-        &CampaignCategory::Zakat,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
         &50_000_000,
         &recipient,
     );
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RejectExcess);
 
-    let campaign_id_2 = client.create_campaign(
+    client.donate(&donor, &campaign_id, &70_000_000, &false);
+}
+
+#[test]
+fn test_campaign_refund_excess() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Campaign 2"),
-        &String::from_str(&env, "Description 2"),
-        &CampaignCategory::Pendidikan,
-        &100_000_000,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
         &recipient,
     );
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
 
-    // Donate to both campaigns
-    client.donate(&donor1, &campaign_id_1, &20_000_000, &false);
-    client.donate(&donor2, &campaign_id_2, &30_000_000, &false);
-    client.donate(&donor1, &campaign_id_2, &15_000_000, &true);
+    client.donate(&donor, &campaign_id, &30_000_000, &false);
+    let refunded = client.donate(&donor, &campaign_id, &30_000_000, &false);
+    assert_eq!(refunded, 10_000_000);
 
-    let all_donations = client.get_all_donations();
-    assert_eq!(all_donations.len(), 3);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 50_000_000);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+
+    // Only the accepted part is recorded
+    let donations = client.get_campaign_donations(&campaign_id, &0, &100);
+    assert_eq!(donations.get(1).unwrap().amount, 20_000_000);
+    assert_eq!(client.get_donor_total(&donor), 50_000_000);
 }
 
 #[test]
-fn test_get_total_donations() {
+fn test_campaign_redirect_excess() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -344,80 +375,171 @@ fn test_get_total_donations() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
-    // Create 3 campaigns
-    let campaign_id_1 = client.create_campaign(
+    let general_fund = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Campaign 1"),
-        &String::from_str(&env, "Description 1"),
+        &String::from_str(&env, "Dana Umum"),
+        &String::from_str(&env, "Dana umum yayasan"),
         &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
         &50_000_000,
         &recipient,
     );
+    client.set_excess_policy(
+        &admin,
+        &campaign_id,
+        &ExcessPolicy::RedirectExcess(general_fund),
+    );
 
-    let campaign_id_2 = client.create_campaign(
+    let refunded = client.donate(&donor, &campaign_id, &70_000_000, &true);
+    assert_eq!(refunded, 0);
+
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 50_000_000);
+    assert_eq!(client.get_campaign(&general_fund).current_amount, 20_000_000);
+
+    // Forwarded part is recorded against the general fund for the same donor
+    let forwarded = client.get_campaign_donations(&general_fund, &0, &100).get(0).unwrap();
+    assert_eq!(forwarded.donor, donor);
+    assert_eq!(forwarded.amount, 20_000_000);
+    assert!(forwarded.is_anonymous);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+    assert!(client.verify_integrity(&general_fund).is_consistent);
+}
+
+#[test]
+fn test_campaign_redirect_excess_to_closed_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let general_fund = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Campaign 2"),
-        &String::from_str(&env, "Description 2"),
-        &CampaignCategory::Pendidikan,
-        &100_000_000,
+        &String::from_str(&env, "Dana Umum"),
+        &String::from_str(&env, "Dana umum yayasan"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
         &recipient,
     );
-
-    let campaign_id_3 = client.create_campaign(
+    let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Campaign 3"),
-        &String::from_str(&env, "Description 3"),
-        &CampaignCategory::Kesehatan,
-        &75_000_000,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
         &recipient,
     );
+    client.set_excess_policy(
+        &admin,
+        &campaign_id,
+        &ExcessPolicy::RedirectExcess(general_fund),
+    );
+    client.close_campaign(&admin, &general_fund);
 
-    // Donate to all campaigns
-    client.donate(&donor, &campaign_id_1, &20_000_000, &false);
-    client.donate(&donor, &campaign_id_2, &30_000_000, &false);
-    client.donate(&donor, &campaign_id_3, &15_000_000, &false);
+    // The closed target cannot take the excess, so it is refunded
+    let refunded = client.donate(&donor, &campaign_id, &70_000_000, &false);
+    assert_eq!(refunded, 20_000_000);
 
-    let total = client.get_total_donations();
-    assert_eq!(total, 65_000_000); // 6.5 XLM total
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 50_000_000);
+    assert_eq!(client.get_campaign(&general_fund).current_amount, 0);
+    assert_eq!(client.get_campaign_donations(&general_fund, &0, &10).len(), 0);
 }
 
 #[test]
-fn test_close_campaign() {
+fn test_campaign_redirect_excess_to_completed_target() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
+    let general_fund = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "Dana Umum"),
+        &String::from_str(&env, "Dana umum yayasan"),
         &CampaignCategory::Zakat,
+        &10_000_000,
+        &recipient,
+    );
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
         &50_000_000,
         &recipient,
     );
+    client.set_excess_policy(
+        &admin,
+        &campaign_id,
+        &ExcessPolicy::RedirectExcess(general_fund),
+    );
+    client.donate(&donor, &general_fund, &10_000_000, &false);
 
-    // Close campaign
-    client.close_campaign(&admin, &campaign_id);
+    let refunded = client.donate(&donor, &campaign_id, &70_000_000, &false);
+    assert_eq!(refunded, 20_000_000);
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.status, CampaignStatus::Closed);
+    assert_eq!(client.get_campaign(&general_fund).current_amount, 10_000_000);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+    assert!(client.verify_integrity(&general_fund).is_consistent);
 }
 
 #[test]
-#[should_panic(expected = "Campaign is not active")]
-fn test_donate_to_closed_campaign() {
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_campaign_redirect_to_self() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.set_excess_policy(
+        &admin,
+        &campaign_id,
+        &ExcessPolicy::RedirectExcess(campaign_id),
+    );
+}
+
+#[test]
+fn test_flexible_goal() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -425,29 +547,43 @@ fn test_donate_to_closed_campaign() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
     let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
+        &String::from_str(&env, "Zakat Mal"),
+        &String::from_str(&env, "Penghimpunan zakat berkelanjutan"),
         &CampaignCategory::Zakat,
         &50_000_000,
         &recipient,
     );
+    client.set_goal_type(&admin, &campaign_id, &GoalType::Flexible);
 
-    // Close campaign
-    client.close_campaign(&admin, &campaign_id);
+    // Passing the target does not complete the campaign
+    client.donate(&donor, &campaign_id, &70_000_000, &false);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.target_amount, 50_000_000);
 
-    // Try to donate to closed campaign (should panic)
+    // Withdrawals keep the campaign open
+    assert_eq!(client.withdraw(&recipient, &campaign_id), 70_000_000);
     client.donate(&donor, &campaign_id, &10_000_000, &false);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Active);
+
+    // Remaining balance can be paid out after closing
+    client.close_campaign(&admin, &campaign_id);
+    assert_eq!(client.withdraw(&recipient, &campaign_id), 10_000_000);
+
+    let report = client.verify_integrity(&campaign_id);
+    assert_eq!(report.withdrawn_amount, 80_000_000);
+    assert!(report.is_consistent);
 }
 
 #[test]
-fn test_withdraw_completed_campaign() {
+fn test_open_ended_goal() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -455,95 +591,91 @@ fn test_withdraw_completed_campaign() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
     let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Withdrawal Test"),
-        &String::from_str(&env, "Test withdrawal"),
+        &String::from_str(&env, "Zakat Mal"),
+        &String::from_str(&env, "Penghimpunan zakat berkelanjutan"),
         &CampaignCategory::Zakat,
         &50_000_000,
         &recipient,
     );
+    client.set_goal_type(&admin, &campaign_id, &GoalType::OpenEnded);
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RejectExcess);
 
-    // Donate to complete campaign
-    client.donate(&donor, &campaign_id, &50_000_000, &false);
-
-    // Withdraw
-    let withdrawn_amount = client.withdraw(&recipient, &campaign_id);
-    assert_eq!(withdrawn_amount, 50_000_000);
-
-    // Check campaign is closed and amount is 0
     let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.current_amount, 0);
-    assert_eq!(campaign.status, CampaignStatus::Closed);
+    assert_eq!(campaign.goal_type, GoalType::OpenEnded);
+    assert_eq!(campaign.target_amount, 0);
+
+    // No target, so nothing is ever excess
+    client.donate(&donor, &campaign_id, &500_000_000, &false);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Active);
+    assert_eq!(client.withdraw(&recipient, &campaign_id), 500_000_000);
 }
 
 #[test]
-#[should_panic(expected = "Campaign must be completed to withdraw")]
-fn test_withdraw_active_campaign() {
+#[should_panic(expected = "Open-ended campaigns have no target")]
+fn test_open_ended_goal_update_target() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
+    let title = String::from_str(&env, "Zakat Mal");
+    let description = String::from_str(&env, "Penghimpunan zakat berkelanjutan");
     let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
+        &title,
+        &description,
         &CampaignCategory::Zakat,
         &50_000_000,
         &recipient,
     );
+    client.set_goal_type(&admin, &campaign_id, &GoalType::OpenEnded);
 
-    // Try to withdraw from active campaign (should panic)
-    client.withdraw(&recipient, &campaign_id);
+    client.update_campaign(&admin, &campaign_id, &title, &description, &80_000_000);
 }
 
 #[test]
-#[should_panic(expected = "Only recipient can withdraw")]
-fn test_withdraw_wrong_recipient() {
+#[should_panic(expected = "Goal type can only be changed before completion")]
+fn test_set_goal_type_completed() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let wrong_recipient = Address::generate(&env);
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
     let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
         &50_000_000,
         &recipient,
     );
-
-    // Complete campaign
     client.donate(&donor, &campaign_id, &50_000_000, &false);
 
-    // Try to withdraw with wrong recipient (should panic)
-    client.withdraw(&wrong_recipient, &campaign_id);
+    client.set_goal_type(&admin, &campaign_id, &GoalType::Flexible);
 }
 
 #[test]
-#[should_panic(expected = "Amount must be greater than 0")]
-fn test_donate_zero_amount() {
+fn test_full_campaign_skips_empty_donation() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -551,43 +683,3693 @@ fn test_donate_zero_amount() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
     let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &100,
         &recipient,
     );
+    client.set_goal_type(&admin, &campaign_id, &GoalType::Flexible);
+    client.donate(&donor, &campaign_id, &100, &false);
+    client.set_goal_type(&admin, &campaign_id, &GoalType::Fixed);
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
 
-    // Try to donate 0 amount (should panic)
-    client.donate(&donor, &campaign_id, &0, &false);
+    // The campaign is still active but has no room left
+    assert_eq!(client.donate(&donor, &campaign_id, &10, &false), 10);
+    assert_eq!(client.get_campaign_donations(&campaign_id, &0, &10).len(), 1);
 }
 
 #[test]
-#[should_panic(expected = "Target amount must be greater than 0")]
-fn test_create_campaign_zero_target() {
+fn test_stretch_goals() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
+
     client.initialize(&admin);
 
-    // Try to create campaign with 0 target (should panic)
-    client.create_campaign(
+    let campaign_id = client.create_campaign(
         &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &0,
+        &String::from_str(&env, "Banjir Demak"),
+        &String::from_str(&env, "Bantuan korban banjir"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
         &recipient,
     );
-}
\ No newline at end of file
+    client.donate(&donor, &campaign_id, &50_000_000, &false);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Completed);
+
+    // Stretch goal reopens the completed campaign
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    assert_eq!(client.add_stretch_goal(&admin, &campaign_id, &80_000_000), 0);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.target_amount, 80_000_000);
+    assert_eq!(client.get_campaigns_by_status(&CampaignStatus::Active).len(), 1);
+
+    // A second stretch goal queues behind the first
+    assert_eq!(client.add_stretch_goal(&admin, &campaign_id, &100_000_000), 1);
+    assert_eq!(client.get_campaign(&campaign_id).target_amount, 80_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    client.donate(&donor, &campaign_id, &40_000_000, &false);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.target_amount, 100_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 3_000);
+    client.donate(&donor, &campaign_id, &10_000_000, &false);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+    assert_eq!(campaign.target_amount, 100_000_000);
+
+    let goals = client.get_stretch_goals(&campaign_id);
+    assert_eq!(goals.len(), 2);
+    assert_eq!(goals.get(0).unwrap().added_at, 1_000);
+    assert_eq!(goals.get(0).unwrap().reached_at, 2_000);
+    assert_eq!(goals.get(1).unwrap().reached_at, 3_000);
+}
+
+#[test]
+fn test_stretch_goals_passed_in_one_donation() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Banjir Demak"),
+        &String::from_str(&env, "Bantuan korban banjir"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+    client.add_stretch_goal(&admin, &campaign_id, &60_000_000);
+    client.add_stretch_goal(&admin, &campaign_id, &70_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    client.donate(&donor, &campaign_id, &75_000_000, &false);
+
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Completed);
+    for goal in client.get_stretch_goals(&campaign_id).iter() {
+        assert_eq!(goal.reached_at, 5_000);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Stretch target must exceed the current goal")]
+fn test_stretch_goal_below_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Banjir Demak"),
+        &String::from_str(&env, "Bantuan korban banjir"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.add_stretch_goal(&admin, &campaign_id, &50_000_000);
+}
+
+#[test]
+fn test_redirect_funds() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let from = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Sumur Desa"),
+        &String::from_str(&env, "Pembangunan sumur"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient,
+    );
+    let to = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Klinik Desa"),
+        &String::from_str(&env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &100_000_000,
+        &recipient,
+    );
+
+    // Closed before reaching the target, so the balance is stranded
+    client.donate(&donor, &from, &30_000_000, &false);
+    client.close_campaign(&admin, &from);
+
+    client.set_redirect_grace_period(&admin, &86_400);
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    let executable_at = client.announce_redirect(&admin, &from, &to, &20_000_000);
+    assert_eq!(executable_at, 87_400);
+    assert_eq!(client.get_pending_redirect(&from).unwrap().amount, 20_000_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 87_400);
+    client.redirect_funds(&admin, &from, &to, &20_000_000);
+
+    assert_eq!(client.get_campaign(&from).current_amount, 10_000_000);
+    assert_eq!(client.get_campaign(&to).current_amount, 20_000_000);
+    assert_eq!(client.get_pending_redirect(&from), None);
+
+    // Both campaigns show the move in their history
+    let expected = FundRedirect {
+        from_campaign: from,
+        to_campaign: to,
+        amount: 20_000_000,
+        announced_at: 1_000,
+        executed_at: 87_400,
+    };
+    assert_eq!(client.get_redirect_history(&from).get(0).unwrap(), expected);
+    assert_eq!(client.get_redirect_history(&to).get(0).unwrap(), expected);
+
+    let report = client.verify_integrity(&from);
+    assert_eq!(report.redirected_out, 20_000_000);
+    assert!(report.is_consistent);
+    let report = client.verify_integrity(&to);
+    assert_eq!(report.redirected_in, 20_000_000);
+    assert!(report.is_consistent);
+}
+
+// Stranded 30 in a closed campaign, announced for a move into a campaign at 90/100
+fn setup_redirect_into_nearly_full(
+    env: &Env,
+    client: &ZakatContractClient,
+    policy: ExcessPolicy,
+) -> (Address, u32, u32) {
+    let admin = Address::generate(env);
+    let recipient = Address::generate(env);
+    let donor = Address::generate(env);
+
+    client.initialize(&admin);
+
+    let from = client.create_campaign(
+        &admin,
+        &String::from_str(env, "Sumur Desa"),
+        &String::from_str(env, "Pembangunan sumur"),
+        &CampaignCategory::Kesehatan,
+        &50,
+        &recipient,
+    );
+    let to = client.create_campaign(
+        &admin,
+        &String::from_str(env, "Klinik Desa"),
+        &String::from_str(env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &100,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &to, &policy);
+
+    client.donate(&donor, &from, &30, &false);
+    client.close_campaign(&admin, &from);
+    client.donate(&donor, &to, &90, &false);
+
+    client.set_redirect_grace_period(&admin, &86_400);
+    client.announce_redirect(&admin, &from, &to, &30);
+    env.ledger().with_mut(|l| l.timestamp += 86_400);
+
+    (admin, from, to)
+}
+
+#[test]
+fn test_redirect_funds_capped_by_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+
+    let (admin, from, to) =
+        setup_redirect_into_nearly_full(&env, &client, ExcessPolicy::RefundExcess);
+
+    // Only the room left in the target moves, the rest stays in the source
+    assert_eq!(client.redirect_funds(&admin, &from, &to, &30), 10);
+
+    assert_eq!(client.get_campaign(&from).current_amount, 20);
+    let target = client.get_campaign(&to);
+    assert_eq!(target.current_amount, 100);
+    assert_eq!(target.status, CampaignStatus::Completed);
+    assert_eq!(client.get_redirect_history(&to).get(0).unwrap().amount, 10);
+    assert!(client.verify_integrity(&from).is_consistent);
+    assert!(client.verify_integrity(&to).is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_redirect_funds_reject_excess_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+
+    let (admin, from, to) =
+        setup_redirect_into_nearly_full(&env, &client, ExcessPolicy::RejectExcess);
+
+    client.redirect_funds(&admin, &from, &to, &30);
+}
+
+#[test]
+#[should_panic(expected = "Target campaign is not accepting funds")]
+fn test_redirect_funds_to_completed_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+
+    let (admin, from, to) =
+        setup_redirect_into_nearly_full(&env, &client, ExcessPolicy::AcceptAll);
+
+    // The target completes between the notice and the move
+    client.donate(&Address::generate(&env), &to, &10, &false);
+    client.redirect_funds(&admin, &from, &to, &30);
+}
+
+#[test]
+#[should_panic(expected = "Grace period has not passed")]
+fn test_redirect_funds_before_grace_period() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let from = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Sumur Desa"),
+        &String::from_str(&env, "Pembangunan sumur"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient,
+    );
+    let to = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Klinik Desa"),
+        &String::from_str(&env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &100_000_000,
+        &recipient,
+    );
+
+    // Completed but never withdrawn
+    client.donate(&donor, &from, &50_000_000, &false);
+    client.announce_redirect(&admin, &from, &to, &50_000_000);
+
+    let grace = client.get_redirect_grace_period();
+    env.ledger().with_mut(|l| l.timestamp += grace - 1);
+    client.redirect_funds(&admin, &from, &to, &50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Zakat funds can only move between zakat campaigns")]
+fn test_redirect_zakat_to_non_zakat() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let from = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+    let to = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Klinik Desa"),
+        &String::from_str(&env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &100_000_000,
+        &recipient,
+    );
+
+    client.donate(&donor, &from, &50_000_000, &false);
+    client.announce_redirect(&admin, &from, &to, &50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "No matching redirect notice")]
+fn test_redirect_funds_without_notice() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let from = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Sumur Desa"),
+        &String::from_str(&env, "Pembangunan sumur"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient,
+    );
+    let to = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Klinik Desa"),
+        &String::from_str(&env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &100_000_000,
+        &recipient,
+    );
+
+    client.donate(&donor, &from, &50_000_000, &false);
+    client.announce_redirect(&admin, &from, &to, &10_000_000);
+
+    // Amount differs from the notice
+    let grace = client.get_redirect_grace_period();
+    env.ledger().with_mut(|l| l.timestamp += grace);
+    client.redirect_funds(&admin, &from, &to, &50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Grace period is too short")]
+fn test_redirect_grace_period_minimum() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    client.set_redirect_grace_period(&admin, &0);
+}
+
+#[test]
+fn test_category_pool_allocation() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate_to_category(&donor1, &CampaignCategory::Pendidikan, &30_000_000);
+    client.donate_to_category(&donor2, &CampaignCategory::Pendidikan, &20_000_000);
+    assert_eq!(client.get_pool_balance(&CampaignCategory::Pendidikan), 50_000_000);
+    assert_eq!(client.get_pool_balance(&CampaignCategory::Zakat), 0);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &40_000_000);
+
+    assert_eq!(client.get_pool_balance(&CampaignCategory::Pendidikan), 10_000_000);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 40_000_000);
+
+    // Oldest pool donations are drawn first
+    let allocation = client.get_pool_allocations(&CampaignCategory::Pendidikan, &0, &100).get(0).unwrap();
+    assert_eq!(allocation.campaign_id, campaign_id);
+    assert_eq!(allocation.sources.len(), 2);
+    assert_eq!(allocation.sources.get(0).unwrap().amount, 30_000_000);
+    assert_eq!(allocation.sources.get(1).unwrap().amount, 10_000_000);
+
+    let trace = client.trace_pool_donations(&donor2, &0, &100);
+    assert_eq!(trace.len(), 1);
+    assert_eq!(trace.get(0).unwrap().campaign_id, campaign_id);
+    assert_eq!(trace.get(0).unwrap().amount, 10_000_000);
+    assert_eq!(trace.get(0).unwrap().timestamp, 1_000);
+    assert_eq!(client.get_pool_donations(&CampaignCategory::Pendidikan, &0, &100).get(1).unwrap().allocated, 10_000_000);
+
+    // Pool funds complete the campaign like donations do
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &10_000_000);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Completed);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+
+    // The rest of the partly used donation funded the second allocation
+    let page = client.get_pool_allocations(&CampaignCategory::Pendidikan, &1, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().sources.get(0).unwrap().donor, donor2);
+    assert_eq!(client.trace_pool_donations(&donor2, &1, &10).get(0).unwrap().amount, 10_000_000);
+    assert_eq!(client.get_pool_donations(&CampaignCategory::Pendidikan, &1, &1).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not in this category")]
+fn test_allocate_from_pool_wrong_category() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Klinik Desa"),
+        &String::from_str(&env, "Operasional klinik"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate_to_category(&donor, &CampaignCategory::Pendidikan, &30_000_000);
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &30_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds pool balance")]
+fn test_allocate_from_pool_exceeds_balance() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate_to_category(&donor, &CampaignCategory::Pendidikan, &10_000_000);
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &10_000_001);
+}
+
+#[test]
+fn test_allocate_from_pool_refund_excess() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
+    client.donate(&donor, &campaign_id, &30_000_000, &false);
+
+    // Only the room left under the target leaves the pool
+    client.donate_to_category(&donor, &CampaignCategory::Pendidikan, &40_000_000);
+    let moved = client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &40_000_000);
+    assert_eq!(moved, 20_000_000);
+    assert_eq!(client.get_pool_balance(&CampaignCategory::Pendidikan), 20_000_000);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 50_000_000);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Completed);
+    assert_eq!(client.get_pool_donations(&CampaignCategory::Pendidikan, &0, &100).get(0).unwrap().allocated, 20_000_000);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_allocate_from_pool_reject_excess() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RejectExcess);
+
+    client.donate_to_category(&donor, &CampaignCategory::Pendidikan, &60_000_000);
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &60_000_000);
+}
+
+#[test]
+fn test_donate_batch() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let zakat = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+    let school = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+
+    let receipt = client.donate_batch(
+        &donor,
+        &vec![&env, (zakat, 10_000_000), (school, 20_000_000)],
+        &false,
+    );
+    assert_eq!(receipt.refunded, 0);
+    assert_eq!(client.get_batch_donations(&receipt.batch_id).len(), 2);
+
+    assert_eq!(client.get_campaign(&zakat).current_amount, 10_000_000);
+    assert_eq!(client.get_campaign(&school).current_amount, 20_000_000);
+    assert_eq!(client.get_donor_donations(&donor, &0, &10).len(), 2);
+}
+
+#[test]
+fn test_donate_batch_is_atomic() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let open = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+    let closed = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+    client.close_campaign(&admin, &closed);
+
+    let result = client.try_donate_batch(
+        &donor,
+        &vec![&env, (open, 10_000_000), (closed, 20_000_000)],
+        &false,
+    );
+    assert!(result.is_err());
+
+    // Nothing from the failed batch is recorded
+    assert_eq!(client.get_campaign(&open).current_amount, 0);
+    assert_eq!(client.get_all_donations(&0, &100).len(), 0);
+}
+
+#[test]
+fn test_token_custody() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&donor, &100_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+    assert_eq!(client.get_token(), Some(asset.address()));
+
+    let zakat = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+    let school = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &school, &ExcessPolicy::RefundExcess);
+
+    // Refunded excess never leaves the donor
+    let receipt = client.donate_batch(
+        &donor,
+        &vec![&env, (zakat, 10_000_000), (school, 60_000_000)],
+        &false,
+    );
+    assert_eq!(receipt.refunded, 10_000_000);
+    assert_eq!(token_client.balance(&donor), 40_000_000);
+    assert_eq!(token_client.balance(&contract_id), 60_000_000);
+
+    client.donate_to_category(&donor, &CampaignCategory::Kesehatan, &5_000_000);
+    let report = client.verify_custody();
+    assert_eq!(report.token_balance, 65_000_000);
+    assert!(report.is_consistent);
+
+    // Withdrawals pay out real tokens
+    client.withdraw(&recipient, &school);
+    assert_eq!(token_client.balance(&recipient), 50_000_000);
+    assert!(client.verify_custody().is_consistent);
+    assert!(client.verify_integrity(&school).is_consistent);
+
+    // Tokens sent straight to the contract are surplus, not a mismatch
+    token_client.transfer(&donor, &contract_id, &1);
+    let report = client.verify_custody();
+    assert_eq!(report.surplus, 1);
+    assert!(report.is_consistent);
+    assert!(client.verify_integrity(&school).is_consistent);
+
+    // A shortfall breaks custody and integrity
+    token_client.burn(&contract_id, &2);
+    let report = client.verify_custody();
+    assert_eq!(report.surplus, 0);
+    assert!(!report.is_consistent);
+    assert!(!client.verify_integrity(&school).is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Token must be set before funds are held")]
+fn test_set_token_after_donations() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.donate_to_category(&donor, &CampaignCategory::Zakat, &5_000_000);
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    client.set_token(&admin, &asset.address());
+}
+
+#[test]
+fn test_donate_split() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let mut ids = Vec::new(&env);
+    for category in [
+        CampaignCategory::Zakat,
+        CampaignCategory::Pendidikan,
+        CampaignCategory::BencanaAlam,
+    ] {
+        ids.push_back(client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Campaign"),
+            &String::from_str(&env, "Description"),
+            &category,
+            &1_000_000_000,
+            &recipient,
+        ));
+    }
+
+    // First split gets a fresh batch ID after an earlier batch
+    client.donate_batch(&donor, &vec![&env, (ids.get(0).unwrap(), 1_000)], &false);
+
+    // 50% / 30% / 20% of 10_000_001: the largest share takes the dust
+    let receipt = client.donate_split(
+        &donor,
+        &10_000_001,
+        &vec![
+            &env,
+            (ids.get(0).unwrap(), 5_000),
+            (ids.get(1).unwrap(), 3_000),
+            (ids.get(2).unwrap(), 2_000),
+        ],
+        &false,
+    );
+    assert_eq!(receipt.batch_id, 2);
+    assert_eq!(receipt.refunded, 0);
+
+    let legs = client.get_batch_donations(&receipt.batch_id);
+    assert_eq!(legs.len(), 3);
+    assert_eq!(legs.get(0).unwrap().amount, 5_000_001);
+    assert_eq!(legs.get(1).unwrap().amount, 3_000_000);
+    assert_eq!(legs.get(2).unwrap().amount, 2_000_000);
+    assert_eq!(client.get_campaign(&ids.get(2).unwrap()).current_amount, 2_000_000);
+
+    // Single donations are not part of any batch
+    client.donate(&donor, &ids.get(1).unwrap(), &1_000, &false);
+    assert_eq!(client.get_batch_donations(&0).len(), 0);
+
+    // Anonymous split where two shares round down to nothing
+    let receipt = client.donate_split(
+        &donor,
+        &3,
+        &vec![
+            &env,
+            (ids.get(0).unwrap(), 3_333),
+            (ids.get(1).unwrap(), 3_334),
+            (ids.get(2).unwrap(), 3_333),
+        ],
+        &true,
+    );
+    let legs = client.get_batch_donations(&receipt.batch_id);
+    assert_eq!(legs.len(), 1);
+    assert_eq!(legs.get(0).unwrap().campaign_id, ids.get(1).unwrap());
+    assert_eq!(legs.get(0).unwrap().amount, 3);
+    assert!(legs.get(0).unwrap().is_anonymous);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_donate_split_shares_must_sum() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate_split(&donor, &10_000_000, &vec![&env, (campaign_id, 9_999)], &false);
+}
+
+#[test]
+fn test_zakat_fitrah() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah 1446H"),
+        &String::from_str(&env, "2.5 kg beras per jiwa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    client.set_fitrah(&admin, &campaign_id, &450_000, &1_000, &2_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.donate_fitrah(&donor1, &campaign_id, &4);
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    client.donate_fitrah(&donor2, &campaign_id, &1);
+
+    assert_eq!(client.get_persons_covered(&campaign_id), 5);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 2_250_000);
+    assert_eq!(client.get_donor_total(&donor1), 1_800_000);
+
+    // Changing the rate keeps the count
+    client.set_fitrah(&admin, &campaign_id, &500_000, &1_000, &3_000);
+    assert_eq!(client.get_fitrah(&campaign_id).unwrap().persons_covered, 5);
+}
+
+#[test]
+fn test_zakat_fitrah_refunds_partial_person() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah 1446H"),
+        &String::from_str(&env, "2.5 kg beras per jiwa"),
+        &CampaignCategory::Zakat,
+        &250,
+        &recipient,
+    );
+    client.set_fitrah(&admin, &campaign_id, &100, &0, &2_000);
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
+
+    // Room for 2.5 persons: the third person is refunded in full
+    assert_eq!(client.donate_fitrah(&donor, &campaign_id, &3), 100);
+    assert_eq!(client.get_persons_covered(&campaign_id), 2);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 200);
+}
+
+#[test]
+fn test_zakat_fitrah_blocks_moved_funds() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let zakat = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Mal"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &100,
+        &recipient,
+    );
+    let fitrah = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah 1446H"),
+        &String::from_str(&env, "2.5 kg beras per jiwa"),
+        &CampaignCategory::Zakat,
+        &1_000,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &zakat, &ExcessPolicy::RedirectExcess(fitrah));
+    client.set_fitrah(&admin, &fitrah, &100, &0, &2_000);
+
+    // Excess meant for a campaign that is now in fitrah mode is refunded
+    assert_eq!(client.donate(&donor, &zakat, &150, &false), 50);
+    assert_eq!(client.get_campaign(&fitrah).current_amount, 0);
+
+    assert!(client
+        .try_set_excess_policy(&admin, &zakat, &ExcessPolicy::RedirectExcess(fitrah))
+        .is_err());
+    assert!(client.try_announce_redirect(&admin, &zakat, &fitrah, &100).is_err());
+
+    client.donate_to_category(&donor, &CampaignCategory::Zakat, &100);
+    assert!(client
+        .try_allocate_from_pool(&admin, &CampaignCategory::Zakat, &fitrah, &100)
+        .is_err());
+}
+
+#[test]
+#[should_panic(expected = "Outside the fitrah collection window")]
+fn test_zakat_fitrah_after_window() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah 1446H"),
+        &String::from_str(&env, "2.5 kg beras per jiwa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    client.set_fitrah(&admin, &campaign_id, &450_000, &1_000, &2_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 2_001);
+    client.donate_fitrah(&donor, &campaign_id, &1);
+}
+
+#[test]
+#[should_panic(expected = "Use donate_fitrah for fitrah campaigns")]
+fn test_zakat_fitrah_plain_donation() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Fitrah 1446H"),
+        &String::from_str(&env, "2.5 kg beras per jiwa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    client.set_fitrah(&admin, &campaign_id, &450_000, &1_000, &2_000);
+
+    client.donate(&donor, &campaign_id, &450_000, &false);
+}
+
+#[test]
+#[should_panic(expected = "Fitrah mode is only for zakat campaigns")]
+fn test_zakat_fitrah_non_zakat_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    client.set_fitrah(&admin, &campaign_id, &450_000, &1_000, &2_000);
+}
+
+#[test]
+fn test_qurban_units() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Qurban 1446H"),
+        &String::from_str(&env, "Qurban untuk warga desa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    let goat = client.add_qurban_unit(&admin, &campaign_id, &String::from_str(&env, "Kambing"), &3_000_000, &1);
+    let cow = client.add_qurban_unit(&admin, &campaign_id, &String::from_str(&env, "Sapi"), &21_000_000, &7);
+
+    // The target follows the unit prices
+    assert_eq!(client.get_campaign(&campaign_id).target_amount, 24_000_000);
+
+    client.join_qurban(&Address::generate(&env), &campaign_id, &goat);
+    for _ in 0..7 {
+        client.join_qurban(&Address::generate(&env), &campaign_id, &cow);
+    }
+
+    let units = client.get_qurban_units(&campaign_id);
+    assert_eq!(units.get(goat).unwrap().status, QurbanUnitStatus::Full);
+    assert_eq!(units.get(cow).unwrap().status, QurbanUnitStatus::Full);
+    assert_eq!(units.get(cow).unwrap().participants.len(), 7);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 24_000_000);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+}
+
+#[test]
+fn test_qurban_close_reassigns_and_refunds() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Qurban 1446H"),
+        &String::from_str(&env, "Qurban untuk warga desa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    let sapi = String::from_str(&env, "Sapi");
+    let first = client.add_qurban_unit(&admin, &campaign_id, &sapi, &7_000_000, &7);
+    let second = client.add_qurban_unit(&admin, &campaign_id, &sapi, &7_000_000, &7);
+    let third = client.add_qurban_unit(&admin, &campaign_id, &sapi, &7_000_000, &7);
+
+    // 4 + 2 + 2 participants fill one cow and leave one over
+    let mut donors = std::vec::Vec::new();
+    for (unit_id, count) in [(first, 4), (second, 2), (third, 2)] {
+        for _ in 0..count {
+            let donor = Address::generate(&env);
+            asset_admin.mint(&donor, &1_000_000);
+            client.join_qurban(&donor, &campaign_id, &unit_id);
+            donors.push(donor);
+        }
+    }
+
+    client.close_campaign(&admin, &campaign_id);
+
+    let units = client.get_qurban_units(&campaign_id);
+    assert_eq!(units.get(first).unwrap().status, QurbanUnitStatus::Full);
+    assert_eq!(units.get(first).unwrap().participants.len(), 7);
+    assert_eq!(units.get(second).unwrap().status, QurbanUnitStatus::Refunded);
+    assert_eq!(units.get(third).unwrap().status, QurbanUnitStatus::Merged);
+
+    // The participant left over in the second unit gets the share back
+    let refunded = units.get(second).unwrap().participants.get(0).unwrap();
+    assert_eq!(refunded, donors[4]);
+    assert_eq!(token_client.balance(&refunded), 1_000_000);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 7_000_000);
+    assert_eq!(campaign.refunded_amount, 1_000_000);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+
+    // The refunded share no longer counts as given
+    let returns = client.get_donor_returns(&refunded);
+    assert_eq!(returns.len(), 1);
+    assert_eq!(returns.get(0).unwrap().amount, 1_000_000);
+    assert_eq!(client.get_donor_total(&refunded), 0);
+    let statement = client.get_annual_statement(&refunded, &0, &u64::MAX);
+    assert_eq!(statement.total_amount, 0);
+    assert_eq!(statement.donation_count, 1);
+    assert_eq!(statement.by_campaign.get(0).unwrap().amount, 0);
+    assert_eq!(client.get_donor_total(&donors[0]), 1_000_000);
+
+    // The filled cow is paid out after closing
+    client.withdraw(&recipient, &campaign_id);
+    assert_eq!(token_client.balance(&recipient), 7_000_000);
+    assert!(client.verify_custody().is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Qurban unit is full")]
+fn test_qurban_join_full_unit() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Qurban 1446H"),
+        &String::from_str(&env, "Qurban untuk warga desa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    let kambing = String::from_str(&env, "Kambing");
+    let goat = client.add_qurban_unit(&admin, &campaign_id, &kambing, &3_000_000, &1);
+    client.add_qurban_unit(&admin, &campaign_id, &kambing, &3_000_000, &1);
+
+    client.join_qurban(&Address::generate(&env), &campaign_id, &goat);
+    client.join_qurban(&Address::generate(&env), &campaign_id, &goat);
+}
+
+#[test]
+#[should_panic(expected = "Use join_qurban for qurban campaigns")]
+fn test_qurban_plain_donation() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Qurban 1446H"),
+        &String::from_str(&env, "Qurban untuk warga desa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    client.add_qurban_unit(&admin, &campaign_id, &String::from_str(&env, "Sapi"), &21_000_000, &7);
+
+    client.donate(&donor, &campaign_id, &3_000_000, &false);
+}
+
+#[test]
+fn test_qurban_target_locked() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let title = String::from_str(&env, "Qurban 1446H");
+    let description = String::from_str(&env, "Qurban untuk warga desa");
+    let campaign_id = client.create_campaign(
+        &admin,
+        &title,
+        &description,
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+    client.add_qurban_unit(&admin, &campaign_id, &String::from_str(&env, "Sapi"), &21_000_000, &7);
+
+    // The target can only change by adding units
+    assert!(client
+        .try_update_campaign(&admin, &campaign_id, &title, &description, &30_000_000)
+        .is_err());
+    assert!(client.try_add_stretch_goal(&admin, &campaign_id, &42_000_000).is_err());
+    assert!(client.try_set_goal_type(&admin, &campaign_id, &GoalType::Flexible).is_err());
+
+    // Text edits are still allowed
+    client.update_campaign(&admin, &campaign_id, &title, &description, &21_000_000);
+    assert_eq!(client.get_campaign(&campaign_id).target_amount, 21_000_000);
+}
+
+#[test]
+fn test_campaign_mode_exclusive() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..2 {
+        ids.push_back(client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Zakat 1446H"),
+            &String::from_str(&env, "Zakat untuk warga desa"),
+            &CampaignCategory::Zakat,
+            &1_000_000_000,
+            &recipient,
+        ));
+    }
+    let (fitrah, qurban) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+    let sapi = String::from_str(&env, "Sapi");
+
+    client.set_fitrah(&admin, &fitrah, &450_000, &1_000, &2_000);
+    client.add_qurban_unit(&admin, &qurban, &sapi, &21_000_000, &7);
+    assert_eq!(client.get_campaign_mode(&fitrah), Some(CampaignMode::Fitrah));
+    assert_eq!(client.get_campaign_mode(&qurban), Some(CampaignMode::Qurban));
+
+    // Neither mode can be added on top of the other
+    assert!(client.try_add_qurban_unit(&admin, &fitrah, &sapi, &21_000_000, &7).is_err());
+    assert!(client.try_set_fitrah(&admin, &qurban, &450_000, &1_000, &2_000).is_err());
+
+    // The same mode can still be reconfigured
+    client.set_fitrah(&admin, &fitrah, &500_000, &1_000, &3_000);
+    client.add_qurban_unit(&admin, &qurban, &sapi, &21_000_000, &7);
+}
+
+#[test]
+#[should_panic(expected = "Price must split evenly across slots")]
+fn test_qurban_uneven_price() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Qurban 1446H"),
+        &String::from_str(&env, "Qurban untuk warga desa"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    client.add_qurban_unit(&admin, &campaign_id, &String::from_str(&env, "Sapi"), &20_000_000, &7);
+}
+
+#[test]
+fn test_waqf_pays_out_yield_only() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+    asset_admin.mint(&donor, &100_000_000);
+    asset_admin.mint(&admin, &10_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Wakaf Sekolah"),
+        &String::from_str(&env, "Wakaf produktif untuk sekolah"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+    client.set_fund_type(&admin, &campaign_id, &FundType::Waqf);
+    assert_eq!(client.get_fund_type(&campaign_id), FundType::Waqf);
+
+    // Reaching the target does not release the principal
+    client.donate(&donor, &campaign_id, &100_000_000, &false);
+    assert_eq!(client.get_campaign(&campaign_id).status, CampaignStatus::Completed);
+    assert!(client.try_withdraw(&recipient, &campaign_id).is_err());
+
+    client.record_yield(&admin, &campaign_id, &4_000_000);
+    assert_eq!(client.withdraw(&recipient, &campaign_id), 4_000_000);
+    client.record_yield(&admin, &campaign_id, &1_000_000);
+    assert_eq!(client.withdraw(&recipient, &campaign_id), 1_000_000);
+
+    let fund = client.get_waqf_yield(&campaign_id).unwrap();
+    assert_eq!(fund.recorded, 5_000_000);
+    assert_eq!(fund.withdrawn, 5_000_000);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 100_000_000);
+    assert_eq!(campaign.withdrawn_amount, 0);
+    assert_eq!(token_client.balance(&recipient), 5_000_000);
+    assert_eq!(token_client.balance(&contract_id), 100_000_000);
+    assert!(client.verify_custody().is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Waqf principal is permanent")]
+fn test_waqf_cannot_revert() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Wakaf Sekolah"),
+        &String::from_str(&env, "Wakaf produktif untuk sekolah"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+    client.set_fund_type(&admin, &campaign_id, &FundType::Waqf);
+
+    client.set_fund_type(&admin, &campaign_id, &FundType::Standard);
+}
+
+#[test]
+fn test_waqf_excludes_qurban() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..2 {
+        ids.push_back(client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Wakaf Sekolah"),
+            &String::from_str(&env, "Wakaf produktif untuk sekolah"),
+            &CampaignCategory::Pendidikan,
+            &100_000_000,
+            &recipient,
+        ));
+    }
+    let (waqf, qurban) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+    let sapi = String::from_str(&env, "Sapi");
+
+    client.set_fund_type(&admin, &waqf, &FundType::Waqf);
+    client.add_qurban_unit(&admin, &qurban, &sapi, &21_000_000, &7);
+
+    assert!(client.try_add_qurban_unit(&admin, &waqf, &sapi, &21_000_000, &7).is_err());
+    assert!(client.try_set_fund_type(&admin, &qurban, &FundType::Waqf).is_err());
+    assert_eq!(client.get_fund_type(&qurban), FundType::Standard);
+    assert_eq!(client.get_campaign_mode(&waqf), Some(CampaignMode::Waqf));
+}
+
+#[test]
+#[should_panic(expected = "Waqf principal cannot be redirected")]
+fn test_waqf_redirect() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let waqf = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Wakaf Sekolah"),
+        &String::from_str(&env, "Wakaf produktif untuk sekolah"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+    let school = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+    client.set_fund_type(&admin, &waqf, &FundType::Waqf);
+    client.donate(&donor, &waqf, &100_000_000, &false);
+
+    client.announce_redirect(&admin, &waqf, &school, &100_000_000);
+}
+
+#[test]
+fn test_loan_revolving() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&donor, &12_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Modal Warung"),
+        &String::from_str(&env, "Pinjaman modal tanpa bunga"),
+        &CampaignCategory::UMKM,
+        &12_000_000,
+        &borrower,
+    );
+    client.set_loan_terms(&admin, &campaign_id, &RepaymentMode::Revolving, &3, &1_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.donate(&donor, &campaign_id, &12_000_000, &false);
+    client.withdraw(&borrower, &campaign_id);
+
+    let loan = client.get_loan(&campaign_id).unwrap();
+    assert_eq!(loan.principal, 12_000_000);
+    assert_eq!(loan.disbursed_at, 1_000);
+
+    let status = client.get_loan_status(&campaign_id);
+    assert_eq!(status.outstanding, 12_000_000);
+    assert_eq!(status.due, 0);
+    assert_eq!(status.next_due_at, 2_000);
+    assert!(!status.is_overdue);
+
+    // First installment date passes unpaid
+    env.ledger().with_mut(|l| l.timestamp = 2_500);
+    let status = client.get_loan_status(&campaign_id);
+    assert_eq!(status.due, 4_000_000);
+    assert!(status.is_overdue);
+
+    assert_eq!(client.repay(&borrower, &campaign_id, &4_000_000), 8_000_000);
+    assert!(!client.get_loan_status(&campaign_id).is_overdue);
+
+    // Repayments fund the next business through the pool, not as a gift
+    assert_eq!(client.get_pool_balance(&CampaignCategory::UMKM), 4_000_000);
+    let repayment = client.get_pool_donations(&CampaignCategory::UMKM, &0, &100).get(0).unwrap();
+    assert_eq!(repayment.donor, contract_id);
+    assert_eq!(token_client.balance(&borrower), 8_000_000);
+    assert!(client.verify_custody().is_consistent);
+
+    env.ledger().with_mut(|l| l.timestamp = 9_000);
+    let status = client.get_loan_status(&campaign_id);
+    assert_eq!(status.due, 12_000_000);
+    assert_eq!(status.next_due_at, 0);
+}
+
+#[test]
+fn test_loan_return_to_donors() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+    asset_admin.mint(&donor1, &3_000_000);
+    asset_admin.mint(&donor2, &1_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Modal Warung"),
+        &String::from_str(&env, "Pinjaman modal tanpa bunga"),
+        &CampaignCategory::UMKM,
+        &4_000_000,
+        &borrower,
+    );
+    client.set_loan_terms(&admin, &campaign_id, &RepaymentMode::ReturnToDonors, &2, &1_000);
+
+    client.donate(&donor1, &campaign_id, &3_000_000, &false);
+    client.donate(&donor2, &campaign_id, &1_000_000, &false);
+    client.withdraw(&borrower, &campaign_id);
+
+    // Donors get repayments back by their share of the loan
+    client.repay(&borrower, &campaign_id, &2_000_001);
+    assert_eq!(token_client.balance(&donor1), 1_500_001);
+    assert_eq!(token_client.balance(&donor2), 500_000);
+    assert_eq!(client.get_pool_balance(&CampaignCategory::UMKM), 0);
+    assert_eq!(client.get_loan_status(&campaign_id).outstanding, 1_999_999);
+    assert!(client.verify_custody().is_consistent);
+}
+
+#[test]
+fn test_loan_repayment_in_annual_statement() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&donor, &4_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Modal Warung"),
+        &String::from_str(&env, "Pinjaman modal tanpa bunga"),
+        &CampaignCategory::UMKM,
+        &4_000_000,
+        &borrower,
+    );
+    client.set_loan_terms(&admin, &campaign_id, &RepaymentMode::ReturnToDonors, &2, &1_000);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.donate(&donor, &campaign_id, &3_000_000, &false);
+    client.donate(&donor, &campaign_id, &1_000_000, &true);
+    client.withdraw(&borrower, &campaign_id);
+
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    client.repay(&borrower, &campaign_id, &2_000_000);
+    assert_eq!(token_client.balance(&donor), 2_000_000);
+
+    // Public and anonymous gifts are returned by their own share
+    let returns = client.get_donor_returns(&donor);
+    assert_eq!(returns.len(), 1);
+    assert_eq!(returns.get(0).unwrap().amount, 1_500_000);
+    assert_eq!(client.get_donor_total(&donor), 1_500_000);
+
+    // Before the repayment the statement shows the full gift
+    let statement = client.get_annual_statement(&donor, &0, &2_000);
+    assert_eq!(statement.total_amount, 4_000_000);
+
+    // A period with the repayment nets it out
+    let statement = client.get_annual_statement(&donor, &0, &10_000);
+    assert_eq!(statement.total_amount, 2_000_000);
+    assert_eq!(statement.donation_count, 2);
+    let entry = statement.by_campaign.get(0).unwrap();
+    assert_eq!(entry.amount, 2_000_000);
+    assert_eq!(entry.donation_count, 2);
+    for total in statement.by_category.iter() {
+        if total.category == CampaignCategory::UMKM {
+            assert_eq!(total.amount, 2_000_000);
+        }
+    }
+
+    // The repayment is not listed as a gift from the borrower
+    let statement = client.get_annual_statement(&borrower, &0, &10_000);
+    assert_eq!(statement.total_amount, 0);
+}
+
+#[test]
+fn test_loan_return_to_donors_mixed_funding() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let pool_donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+    asset_admin.mint(&donor, &100);
+    asset_admin.mint(&pool_donor, &900);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Modal Warung"),
+        &String::from_str(&env, "Pinjaman modal tanpa bunga"),
+        &CampaignCategory::UMKM,
+        &1_000,
+        &borrower,
+    );
+    client.set_loan_terms(&admin, &campaign_id, &RepaymentMode::ReturnToDonors, &2, &1_000);
+
+    // 900 from the pool, 100 donated directly
+    client.donate_to_category(&pool_donor, &CampaignCategory::UMKM, &900);
+    client.allocate_from_pool(&admin, &CampaignCategory::UMKM, &campaign_id, &900);
+    client.donate(&donor, &campaign_id, &100, &false);
+    client.withdraw(&borrower, &campaign_id);
+
+    client.repay(&borrower, &campaign_id, &1_000);
+    assert_eq!(token_client.balance(&donor), 100);
+    assert_eq!(client.get_pool_balance(&CampaignCategory::UMKM), 900);
+    assert_eq!(client.get_loan_status(&campaign_id).outstanding, 0);
+    assert!(client.verify_custody().is_consistent);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds outstanding loan")]
+fn test_loan_overpayment() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let borrower = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Modal Warung"),
+        &String::from_str(&env, "Pinjaman modal tanpa bunga"),
+        &CampaignCategory::UMKM,
+        &4_000_000,
+        &borrower,
+    );
+    client.set_loan_terms(&admin, &campaign_id, &RepaymentMode::Revolving, &2, &1_000);
+
+    client.donate(&donor, &campaign_id, &4_000_000, &false);
+    client.withdraw(&borrower, &campaign_id);
+
+    client.repay(&borrower, &campaign_id, &4_000_001);
+}
+
+#[test]
+fn test_scholarship_installments() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let student = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &20_000_000,
+        &recipient,
+    );
+    client.set_scholarship_verifier(&admin, &campaign_id, &verifier);
+    let first = client.add_installment(&admin, &campaign_id, &student, &5_000_000, &1_000);
+    let second = client.add_installment(&admin, &campaign_id, &student, &5_000_000, &2_000);
+
+    client.donate(&donor, &campaign_id, &20_000_000, &false);
+
+    // Neither due nor attested yet
+    assert!(client.try_claim_installment(&student, &campaign_id, &first).is_err());
+    env.ledger().with_mut(|l| l.timestamp = 1_500);
+    assert!(client.try_claim_installment(&student, &campaign_id, &first).is_err());
+
+    client.attest_enrollment(&verifier, &campaign_id, &first);
+    assert_eq!(client.claim_installment(&student, &campaign_id, &first), 5_000_000);
+    assert!(client.try_claim_installment(&student, &campaign_id, &first).is_err());
+
+    // The second semester stays in escrow until it is claimed
+    client.attest_enrollment(&verifier, &campaign_id, &second);
+    assert!(client.try_claim_installment(&student, &campaign_id, &second).is_err());
+
+    let installments = client.get_installments(&campaign_id);
+    assert!(installments.get(first).unwrap().claimed);
+    assert!(!installments.get(second).unwrap().claimed);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 15_000_000);
+    assert_eq!(campaign.withdrawn_amount, 5_000_000);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+}
+
+#[test]
+fn test_scholarship_claim_counts_towards_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let student = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &10_000_000,
+        &recipient,
+    );
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
+    client.set_scholarship_verifier(&admin, &campaign_id, &verifier);
+    let first = client.add_installment(&admin, &campaign_id, &student, &5_000_000, &0);
+
+    // A claim while the campaign is still open
+    client.donate(&donor, &campaign_id, &5_000_000, &false);
+    client.attest_enrollment(&verifier, &campaign_id, &first);
+    client.claim_installment(&student, &campaign_id, &first);
+
+    // The claimed part still counts, so the campaign completes at its target
+    assert_eq!(client.donate(&donor, &campaign_id, &6_000_000, &false), 1_000_000);
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+    assert_eq!(campaign.current_amount, 5_000_000);
+    assert_eq!(campaign.raised_amount(), 10_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Scholarship funds are paid through claim_installment")]
+fn test_scholarship_withdraw() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let student = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &10_000_000,
+        &recipient,
+    );
+    client.add_installment(&admin, &campaign_id, &student, &5_000_000, &1_000);
+    client.donate(&donor, &campaign_id, &10_000_000, &false);
+
+    client.withdraw(&recipient, &campaign_id);
+}
+
+#[test]
+#[should_panic(expected = "Only verifier can attest enrollment")]
+fn test_scholarship_attest_not_verifier() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let verifier = Address::generate(&env);
+    let student = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &10_000_000,
+        &recipient,
+    );
+    client.set_scholarship_verifier(&admin, &campaign_id, &verifier);
+    let installment_id = client.add_installment(&admin, &campaign_id, &student, &5_000_000, &1_000);
+
+    client.attest_enrollment(&student, &campaign_id, &installment_id);
+}
+
+#[test]
+fn test_pay_vendor() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    let asset = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_client = token::TokenClient::new(&env, &asset.address());
+    token::StellarAssetClient::new(&env, &asset.address()).mint(&donor, &30_000_000);
+
+    client.initialize(&admin);
+    client.set_token(&admin, &asset.address());
+    client.set_vendor(&admin, &hospital, &true);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Operasi Jantung"),
+        &String::from_str(&env, "Biaya operasi pasien"),
+        &CampaignCategory::Kesehatan,
+        &30_000_000,
+        &patient,
+    );
+    client.set_vendor_only(&admin, &campaign_id, &true);
+    client.donate(&donor, &campaign_id, &30_000_000, &false);
+
+    let invoice = BytesN::from_array(&env, &[7; 32]);
+    client.pay_vendor(&patient, &campaign_id, &hospital, &25_000_000, &invoice);
+
+    // The same invoice cannot be paid twice
+    assert!(client
+        .try_pay_vendor(&patient, &campaign_id, &hospital, &1_000_000, &invoice)
+        .is_err());
+    // Plain withdrawals are disabled
+    assert!(client.try_withdraw(&patient, &campaign_id).is_err());
+
+    let payments = client.get_vendor_payments(&campaign_id);
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments.get(0).unwrap().invoice_hash, invoice);
+
+    assert_eq!(token_client.balance(&hospital), 25_000_000);
+    assert_eq!(token_client.balance(&patient), 0);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 5_000_000);
+    assert!(client.verify_integrity(&campaign_id).is_consistent);
+    assert!(client.verify_custody().is_consistent);
+}
+
+#[test]
+fn test_pay_vendor_invoice_once_across_campaigns() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_vendor(&admin, &hospital, &true);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..2 {
+        let campaign_id = client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Operasi Jantung"),
+            &String::from_str(&env, "Biaya operasi pasien"),
+            &CampaignCategory::Kesehatan,
+            &30_000_000,
+            &patient,
+        );
+        client.set_vendor_only(&admin, &campaign_id, &true);
+        client.donate(&donor, &campaign_id, &10_000_000, &false);
+        ids.push_back(campaign_id);
+    }
+
+    let invoice = BytesN::from_array(&env, &[7; 32]);
+    client.pay_vendor(&patient, &ids.get(0).unwrap(), &hospital, &5_000_000, &invoice);
+
+    // The same invoice cannot be billed to another campaign
+    assert!(client
+        .try_pay_vendor(&patient, &ids.get(1).unwrap(), &hospital, &5_000_000, &invoice)
+        .is_err());
+    assert_eq!(client.get_campaign(&ids.get(1).unwrap()).current_amount, 10_000_000);
+}
+
+#[test]
+fn test_pay_vendor_counts_towards_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_vendor(&admin, &hospital, &true);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Operasi Jantung"),
+        &String::from_str(&env, "Biaya operasi pasien"),
+        &CampaignCategory::Kesehatan,
+        &100,
+        &patient,
+    );
+    client.set_vendor_only(&admin, &campaign_id, &true);
+    client.set_excess_policy(&admin, &campaign_id, &ExcessPolicy::RefundExcess);
+
+    client.donate(&donor, &campaign_id, &60, &false);
+    client.pay_vendor(&patient, &campaign_id, &hospital, &60, &BytesN::from_array(&env, &[1; 32]));
+    client.donate(&donor, &campaign_id, &40, &false);
+
+    // Paid invoices count as raised, so the campaign completes
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 40);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+    assert!(client.try_donate(&donor, &campaign_id, &10, &false).is_err());
+    assert_eq!(client.get_campaign_donations(&campaign_id, &0, &100).len(), 2);
+}
+
+#[test]
+fn test_vendor_only_excludes_qurban() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..2 {
+        ids.push_back(client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Operasi Jantung"),
+            &String::from_str(&env, "Biaya operasi pasien"),
+            &CampaignCategory::Kesehatan,
+            &30_000_000,
+            &patient,
+        ));
+    }
+    let (vendor_only, qurban) = (ids.get(0).unwrap(), ids.get(1).unwrap());
+    let sapi = String::from_str(&env, "Sapi");
+
+    client.set_vendor_only(&admin, &vendor_only, &true);
+    client.add_qurban_unit(&admin, &qurban, &sapi, &21_000_000, &7);
+
+    assert!(client.try_add_qurban_unit(&admin, &vendor_only, &sapi, &21_000_000, &7).is_err());
+    assert!(client.try_set_vendor_only(&admin, &qurban, &true).is_err());
+
+    // Turning vendor-only off leaves other modes alone
+    client.set_vendor_only(&admin, &qurban, &false);
+    assert_eq!(client.get_campaign_mode(&qurban), Some(CampaignMode::Qurban));
+    client.set_vendor_only(&admin, &vendor_only, &false);
+    assert_eq!(client.get_campaign_mode(&vendor_only), None);
+}
+
+#[test]
+#[should_panic(expected = "Vendor is not approved")]
+fn test_pay_unapproved_vendor() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let patient = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_vendor(&admin, &hospital, &true);
+    client.set_vendor(&admin, &hospital, &false);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Operasi Jantung"),
+        &String::from_str(&env, "Biaya operasi pasien"),
+        &CampaignCategory::Kesehatan,
+        &30_000_000,
+        &patient,
+    );
+    client.set_vendor_only(&admin, &campaign_id, &true);
+    client.donate(&donor, &campaign_id, &30_000_000, &false);
+
+    client.pay_vendor(&patient, &campaign_id, &hospital, &1_000_000, &BytesN::from_array(&env, &[1; 32]));
+}
+
+#[test]
+fn test_spending_reports() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_report_deadline(&admin, &500);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Renovasi Masjid"),
+        &String::from_str(&env, "Renovasi atap masjid"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+    client.set_goal_type(&admin, &campaign_id, &GoalType::Flexible);
+    client.donate(&donor, &campaign_id, &5_000_000, &false);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.withdraw(&recipient, &campaign_id);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_400);
+    assert!(!client.get_spending_summary(&campaign_id).is_overdue);
+
+    // No report within the deadline
+    env.ledger().with_mut(|l| l.timestamp = 1_600);
+    assert!(client.get_spending_summary(&campaign_id).is_overdue);
+    assert_eq!(client.get_overdue_reports(), vec![&env, campaign_id]);
+
+    let doc_hash = BytesN::from_array(&env, &[3; 32]);
+    client.submit_report(
+        &recipient,
+        &campaign_id,
+        &3_000_000,
+        &String::from_str(&env, "Pembelian genteng"),
+        &doc_hash,
+    );
+
+    let summary = client.get_spending_summary(&campaign_id);
+    assert_eq!(summary.withdrawn_amount, 5_000_000);
+    assert_eq!(summary.reported_amount, 3_000_000);
+    assert_eq!(summary.unreported_amount, 2_000_000);
+    assert_eq!(summary.last_report_at, 1_600);
+    assert!(!summary.is_overdue);
+    assert_eq!(client.get_reports(&campaign_id).get(0).unwrap().doc_hash, doc_hash);
+
+    // A new withdrawal starts a new deadline
+    client.donate(&donor, &campaign_id, &1_000_000, &false);
+    env.ledger().with_mut(|l| l.timestamp = 2_000);
+    client.withdraw(&recipient, &campaign_id);
+    env.ledger().with_mut(|l| l.timestamp = 2_501);
+    assert!(client.get_spending_summary(&campaign_id).is_overdue);
+}
+
+#[test]
+fn test_spending_reports_cover_every_payout() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let hospital = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_report_deadline(&admin, &500);
+    client.set_vendor(&admin, &hospital, &true);
+
+    let clinic = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Operasi Jantung"),
+        &String::from_str(&env, "Biaya operasi pasien"),
+        &CampaignCategory::Kesehatan,
+        &10_000_000,
+        &recipient,
+    );
+    client.set_vendor_only(&admin, &clinic, &true);
+    let waqf = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Wakaf Sekolah"),
+        &String::from_str(&env, "Wakaf produktif untuk sekolah"),
+        &CampaignCategory::Pendidikan,
+        &10_000_000,
+        &recipient,
+    );
+    client.set_fund_type(&admin, &waqf, &FundType::Waqf);
+
+    client.donate(&donor, &clinic, &10_000_000, &false);
+    client.donate(&donor, &waqf, &10_000_000, &false);
+
+    env.ledger().with_mut(|l| l.timestamp = 1_000);
+    client.pay_vendor(&recipient, &clinic, &hospital, &4_000_000, &BytesN::from_array(&env, &[1; 32]));
+    client.record_yield(&admin, &waqf, &300_000);
+    client.withdraw(&recipient, &waqf);
+
+    // Vendor payments and waqf returns start the deadline too
+    env.ledger().with_mut(|l| l.timestamp = 1_501);
+    assert_eq!(client.get_overdue_reports(), vec![&env, clinic, waqf]);
+    assert_eq!(client.get_spending_summary(&clinic).withdrawn_amount, 4_000_000);
+
+    client.submit_report(
+        &recipient,
+        &waqf,
+        &300_000,
+        &String::from_str(&env, "Gaji guru"),
+        &BytesN::from_array(&env, &[2; 32]),
+    );
+    let summary = client.get_spending_summary(&waqf);
+    assert_eq!(summary.withdrawn_amount, 300_000);
+    assert_eq!(summary.unreported_amount, 0);
+    assert_eq!(client.get_overdue_reports(), vec![&env, clinic]);
+}
+
+#[test]
+#[should_panic(expected = "Reported spending exceeds withdrawn amount")]
+fn test_spending_report_exceeds_withdrawn() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Renovasi Masjid"),
+        &String::from_str(&env, "Renovasi atap masjid"),
+        &CampaignCategory::BencanaAlam,
+        &5_000_000,
+        &recipient,
+    );
+    client.donate(&donor, &campaign_id, &5_000_000, &false);
+    client.withdraw(&recipient, &campaign_id);
+
+    client.submit_report(
+        &recipient,
+        &campaign_id,
+        &5_000_001,
+        &String::from_str(&env, "Pembelian genteng"),
+        &BytesN::from_array(&env, &[3; 32]),
+    );
+}
+
+#[test]
+fn test_get_all_donations() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    // Create 2 campaigns
+    let campaign_id_1 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 1"),
+        &String::from_str(&env, "Description 1"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    let campaign_id_2 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 2"),
+        &String::from_str(&env, "Description 2"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+
+    // Donate to both campaigns
+    client.donate(&donor1, &campaign_id_1, &20_000_000, &false);
+    client.donate(&donor2, &campaign_id_2, &30_000_000, &false);
+    client.donate(&donor1, &campaign_id_2, &15_000_000, &true);
+
+    let all_donations = client.get_all_donations(&0, &100);
+    assert_eq!(all_donations.len(), 3);
+
+    // Pages are read by donation ID
+    let page = client.get_all_donations(&1, &1);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().amount, 30_000_000);
+    assert_eq!(client.get_all_donations(&3, &10).len(), 0);
+
+    let campaign_page = client.get_campaign_donations(&campaign_id_2, &1, &10);
+    assert_eq!(campaign_page.len(), 1);
+    assert_eq!(campaign_page.get(0).unwrap().amount, 15_000_000);
+    assert_eq!(client.get_campaign_donations(&campaign_id_1, &0, &0).len(), 0);
+}
+
+#[test]
+fn test_get_total_donations() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    // Create 3 campaigns
+    let campaign_id_1 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 1"),
+        &String::from_str(&env, "Description 1"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    let campaign_id_2 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 2"),
+        &String::from_str(&env, "Description 2"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+
+    let campaign_id_3 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 3"),
+        &String::from_str(&env, "Description 3"),
+        &CampaignCategory::Kesehatan,
+        &75_000_000,
+        &recipient,
+    );
+
+    // Donate to all campaigns
+    client.donate(&donor, &campaign_id_1, &20_000_000, &false);
+    client.donate(&donor, &campaign_id_2, &30_000_000, &false);
+    client.donate(&donor, &campaign_id_3, &15_000_000, &false);
+
+    let total = client.get_total_donations();
+    assert_eq!(total, 65_000_000); // 6.5 XLM total
+}
+
+#[test]
+fn test_close_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Close campaign
+    client.close_campaign(&admin, &campaign_id);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Closed);
+}
+
+#[test]
+#[should_panic(expected = "Campaign is not active")]
+fn test_donate_to_closed_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Close campaign
+    client.close_campaign(&admin, &campaign_id);
+
+    // Try to donate to closed campaign (should panic)
+    client.donate(&donor, &campaign_id, &10_000_000, &false);
+}
+
+#[test]
+fn test_withdraw_completed_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Withdrawal Test"),
+        &String::from_str(&env, "Test withdrawal"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Donate to complete campaign
+    client.donate(&donor, &campaign_id, &50_000_000, &false);
+
+    // Withdraw
+    let withdrawn_amount = client.withdraw(&recipient, &campaign_id);
+    assert_eq!(withdrawn_amount, 50_000_000);
+
+    // Check campaign is closed and amount is 0
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 0);
+    assert_eq!(campaign.status, CampaignStatus::Closed);
+}
+
+#[test]
+#[should_panic(expected = "Campaign must be completed to withdraw")]
+fn test_withdraw_active_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Try to withdraw from active campaign (should panic)
+    client.withdraw(&recipient, &campaign_id);
+}
+
+#[test]
+#[should_panic(expected = "Only recipient can withdraw")]
+fn test_withdraw_wrong_recipient() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let wrong_recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Complete campaign
+    client.donate(&donor, &campaign_id, &50_000_000, &false);
+
+    // Try to withdraw with wrong recipient (should panic)
+    client.withdraw(&wrong_recipient, &campaign_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_donate_zero_amount() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Try to donate 0 amount (should panic)
+    client.donate(&donor, &campaign_id, &0, &false);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_create_campaign_zero_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    
+    client.initialize(&admin);
+
+    // Try to create campaign with 0 target (should panic)
+    client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &0,
+        &recipient,
+    );
+}
+#[test]
+fn test_update_campaign_text_and_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa Yatim"),
+        &String::from_str(&env, "Dana pendidkan"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate(&donor, &campaign_id, &20_000_000, &false);
+
+    // Fix typo and raise target
+    let version = client.update_campaign(
+        &admin,
+        &campaign_id,
+        &String::from_str(&env, "Beasiswa Anak Yatim"),
+        &String::from_str(&env, "Dana pendidikan"),
+        &80_000_000,
+    );
+    assert_eq!(version, 1);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.title, String::from_str(&env, "Beasiswa Anak Yatim"));
+    assert_eq!(campaign.target_amount, 80_000_000);
+    assert_eq!(campaign.current_amount, 20_000_000);
+
+    let history = client.get_campaign_history(&campaign_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().title, String::from_str(&env, "Beasiswa Yatim"));
+    assert_eq!(history.get(0).unwrap().target_amount, 50_000_000);
+    assert_eq!(history.get(1).unwrap().version, 1);
+    assert_eq!(history.get(1).unwrap().target_amount, 80_000_000);
+    assert_eq!(history.get(1).unwrap().editor, admin);
+}
+
+#[test]
+fn test_update_completed_campaign_text() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+
+    // Over target, campaign completed
+    client.donate(&donor, &campaign_id, &70_000_000, &false);
+
+    // Text edits are still allowed
+    client.update_campaign(
+        &admin,
+        &campaign_id,
+        &String::from_str(&env, "Emergency Fund"),
+        &String::from_str(&env, "Dana darurat gempa"),
+        &50_000_000,
+    );
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.description, String::from_str(&env, "Dana darurat gempa"));
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+    assert_eq!(client.get_campaign_history(&campaign_id).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Target amount can only be raised")]
+fn test_update_campaign_lower_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.update_campaign(
+        &admin,
+        &campaign_id,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &40_000_000,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Target can only be changed while campaign is active")]
+fn test_update_closed_campaign_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.close_campaign(&admin, &campaign_id);
+
+    client.update_campaign(
+        &admin,
+        &campaign_id,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &60_000_000,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only admin can update campaigns")]
+fn test_update_campaign_not_admin() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.update_campaign(
+        &recipient,
+        &campaign_id,
+        &String::from_str(&env, "Hacked"),
+        &String::from_str(&env, "Description"),
+        &50_000_000,
+    );
+}
+
+#[test]
+fn test_get_donor_donations() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id_1 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 1"),
+        &String::from_str(&env, "Description 1"),
+        &CampaignCategory::Zakat,
+        &100_000_000,
+        &recipient,
+    );
+
+    let campaign_id_2 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 2"),
+        &String::from_str(&env, "Description 2"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
+        &recipient,
+    );
+
+    client.donate(&donor1, &campaign_id_1, &10_000_000, &false);
+    client.donate(&donor2, &campaign_id_1, &20_000_000, &false);
+    client.donate(&donor1, &campaign_id_2, &30_000_000, &true); // anonymous
+    client.donate(&donor1, &campaign_id_2, &40_000_000, &false);
+
+    // Anonymous donation is hidden from the public view
+    let donations = client.get_donor_donations(&donor1, &0, &10);
+    assert_eq!(donations.len(), 2);
+    assert_eq!(donations.get(0).unwrap().amount, 10_000_000);
+    assert_eq!(donations.get(1).unwrap().amount, 40_000_000);
+    assert_eq!(client.get_donor_total(&donor1), 50_000_000);
+    assert_eq!(client.get_donor_total(&donor2), 20_000_000);
+
+    // Donor sees their own anonymous donation
+    let own = client.get_my_donations(&donor1, &0, &10);
+    assert_eq!(own.len(), 3);
+    assert!(own.get(1).unwrap().is_anonymous);
+}
+
+#[test]
+fn test_get_donor_donations_pagination() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Kesehatan,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    for i in 1..=5 {
+        client.donate(&donor, &campaign_id, &(i * 1_000_000), &false);
+    }
+
+    let page = client.get_donor_donations(&donor, &1, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().amount, 2_000_000);
+    assert_eq!(page.get(1).unwrap().amount, 3_000_000);
+
+    let last = client.get_donor_donations(&donor, &4, &10);
+    assert_eq!(last.len(), 1);
+    assert_eq!(last.get(0).unwrap().amount, 5_000_000);
+
+    assert_eq!(client.get_donor_donations(&donor, &5, &10).len(), 0);
+    assert_eq!(client.get_donor_donations(&recipient, &0, &10).len(), 0);
+}
+
+#[test]
+fn test_annual_statement() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let zakat_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Maal"),
+        &String::from_str(&env, "Zakat harta"),
+        &CampaignCategory::Zakat,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    let school_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Dana pendidikan"),
+        &CampaignCategory::Pendidikan,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    // 2024
+    env.ledger().with_mut(|l| l.timestamp = 1_710_000_000);
+    client.donate(&donor, &zakat_id, &5_000_000, &false);
+
+    // 2025
+    env.ledger().with_mut(|l| l.timestamp = 1_740_000_000);
+    client.donate(&donor, &zakat_id, &10_000_000, &true);
+    client.donate(&donor, &school_id, &20_000_000, &false);
+    env.ledger().with_mut(|l| l.timestamp = 1_760_000_000);
+    client.donate(&donor, &zakat_id, &15_000_000, &false);
+
+    // 1 Jan 2025 - 31 Dec 2025
+    let statement = client.get_annual_statement(&donor, &1_735_689_600, &1_767_225_599);
+    assert_eq!(statement.donation_count, 3);
+    assert_eq!(statement.total_amount, 45_000_000);
+
+    assert_eq!(statement.by_category.len(), 5);
+    let zakat = statement.by_category.get(0).unwrap();
+    assert_eq!(zakat.category, CampaignCategory::Zakat);
+    assert_eq!(zakat.amount, 25_000_000);
+    let pendidikan = statement.by_category.get(1).unwrap();
+    assert_eq!(pendidikan.category, CampaignCategory::Pendidikan);
+    assert_eq!(pendidikan.amount, 20_000_000);
+    assert_eq!(statement.by_category.get(4).unwrap().amount, 0);
+
+    assert_eq!(statement.by_campaign.len(), 2);
+    let first = statement.by_campaign.get(0).unwrap();
+    assert_eq!(first.campaign_id, zakat_id);
+    assert_eq!(first.amount, 25_000_000);
+    assert_eq!(first.donation_count, 2);
+    let second = statement.by_campaign.get(1).unwrap();
+    assert_eq!(second.campaign_id, school_id);
+    assert_eq!(second.amount, 20_000_000);
+}
+
+#[test]
+fn test_annual_statement_pool_donations() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Dana pendidikan"),
+        &CampaignCategory::Pendidikan,
+        &1_000_000_000,
+        &recipient,
+    );
+
+    env.ledger().with_mut(|l| l.timestamp = 1_740_000_000);
+    client.donate(&donor, &campaign_id, &20_000_000, &false);
+    client.donate_to_category(&donor, &CampaignCategory::Pendidikan, &30_000_000);
+    client.donate_to_category(&other, &CampaignCategory::Pendidikan, &5_000_000);
+    client.allocate_from_pool(&admin, &CampaignCategory::Pendidikan, &campaign_id, &35_000_000);
+
+    // The pool gift counts once, under its category
+    let statement = client.get_annual_statement(&donor, &1_735_689_600, &1_767_225_599);
+    assert_eq!(statement.donation_count, 2);
+    assert_eq!(statement.total_amount, 50_000_000);
+    assert_eq!(statement.by_category.get(1).unwrap().amount, 50_000_000);
+    assert_eq!(statement.by_campaign.len(), 1);
+    assert_eq!(statement.by_campaign.get(0).unwrap().amount, 20_000_000);
+}
+
+#[test]
+fn test_filter_campaigns() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let id1 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 1"),
+        &String::from_str(&env, "Description 1"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient1,
+    );
+
+    let id2 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 2"),
+        &String::from_str(&env, "Description 2"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient2,
+    );
+
+    let id3 = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Campaign 3"),
+        &String::from_str(&env, "Description 3"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient1,
+    );
+
+    // Complete campaign 2 and close campaign 3
+    client.donate(&donor, &id2, &50_000_000, &false);
+    client.close_campaign(&admin, &id3);
+
+    let pendidikan = client.get_campaigns_by_category(&CampaignCategory::Pendidikan);
+    assert_eq!(pendidikan.len(), 2);
+    assert_eq!(pendidikan.get(0).unwrap().id, id1);
+    assert_eq!(pendidikan.get(1).unwrap().id, id2);
+    assert_eq!(client.get_campaigns_by_category(&CampaignCategory::UMKM).len(), 0);
+
+    let active = client.get_campaigns_by_status(&CampaignStatus::Active);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().id, id1);
+    let completed = client.get_campaigns_by_status(&CampaignStatus::Completed);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed.get(0).unwrap().id, id2);
+    let closed = client.get_campaigns_by_status(&CampaignStatus::Closed);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed.get(0).unwrap().id, id3);
+
+    let by_recipient = client.get_campaigns_by_recipient(&recipient1);
+    assert_eq!(by_recipient.len(), 2);
+    assert_eq!(by_recipient.get(0).unwrap().id, id1);
+    assert_eq!(by_recipient.get(1).unwrap().id, id3);
+
+    // Withdraw moves campaign 2 to closed
+    client.withdraw(&recipient2, &id2);
+    assert_eq!(client.get_campaigns_by_status(&CampaignStatus::Completed).len(), 0);
+    assert_eq!(client.get_campaigns_by_status(&CampaignStatus::Closed).len(), 2);
+}
+
+#[test]
+fn test_campaigns_by_region() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let mut ids = Vec::new(&env);
+    for _ in 0..3 {
+        ids.push_back(client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Gempa Lombok"),
+            &String::from_str(&env, "Tanggap darurat gempa"),
+            &CampaignCategory::BencanaAlam,
+            &50_000_000,
+            &recipient,
+        ));
+    }
+
+    // New campaigns have no region until the admin sets one
+    let lombok = String::from_str(&env, "ID-NB");
+    assert_eq!(client.get_campaign(&ids.get(0).unwrap()).region, String::from_str(&env, ""));
+    for id in ids.iter() {
+        client.set_region(&admin, &id, &lombok);
+    }
+    assert!(client
+        .try_set_region(&recipient, &ids.get(0).unwrap(), &String::from_str(&env, "ID-JT"))
+        .is_err());
+
+    // Closed campaigns drop out of the active view
+    client.close_campaign(&admin, &ids.get(2).unwrap());
+    let active = client.get_active_campaigns_by_region(&lombok);
+    assert_eq!(active.len(), 2);
+    assert_eq!(active.get(1).unwrap().id, ids.get(1).unwrap());
+    assert_eq!(client.get_active_campaigns_by_region(&String::from_str(&env, "ID-JT")).len(), 0);
+}
+
+#[test]
+fn test_category_counts() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    for category in [
+        CampaignCategory::Pendidikan,
+        CampaignCategory::Pendidikan,
+        CampaignCategory::Pendidikan,
+        CampaignCategory::Zakat,
+    ] {
+        client.create_campaign(
+            &admin,
+            &String::from_str(&env, "Campaign"),
+            &String::from_str(&env, "Description"),
+            &category,
+            &50_000_000,
+            &recipient,
+        );
+    }
+
+    client.close_campaign(&admin, &1);
+
+    let active = client.get_category_counts(&CampaignStatus::Active);
+    assert_eq!(active.len(), 5);
+    assert_eq!(
+        active.get(0).unwrap(),
+        CategoryCount { category: CampaignCategory::Zakat, count: 1 }
+    );
+    assert_eq!(
+        active.get(1).unwrap(),
+        CategoryCount { category: CampaignCategory::Pendidikan, count: 2 }
+    );
+    assert_eq!(active.get(2).unwrap().count, 0);
+
+    let closed = client.get_category_counts(&CampaignStatus::Closed);
+    assert_eq!(closed.get(1).unwrap().count, 1);
+}
+
+#[test]
+fn test_data_readable_after_default_ttl() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Zakat Maal"),
+        &String::from_str(&env, "Zakat harta"),
+        &CampaignCategory::Zakat,
+        &100_000_000,
+        &recipient,
+    );
+    client.donate(&donor, &campaign_id, &10_000_000, &false);
+
+    // Writes extended the instance TTL to 30 days
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get_ttl(), 30 * 17_280);
+        assert_eq!(env.storage().persistent().get_ttl(&(DONATIONS, 0u32)), 30 * 17_280);
+        assert_eq!(env.storage().persistent().get_ttl(&(DONOR_INDEX, donor.clone())), 30 * 17_280);
+    });
+
+    // Past the minimum persistent TTL, data is still readable
+    env.ledger().with_mut(|l| l.sequence_number += 20 * 17_280);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.current_amount, 10_000_000);
+    assert_eq!(client.get_campaign_donations(&campaign_id, &0, &10).len(), 1);
+    assert_eq!(client.get_donor_total(&donor), 10_000_000);
+}
+
+#[test]
+fn test_bump_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_ttl_config(&admin, &600_000, &600_000);
+    assert_eq!(client.get_ttl_config(), TtlConfig { threshold: 600_000, extend_to: 600_000 });
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Bantu Korban Gempa"),
+        &String::from_str(&env, "Dana darurat"),
+        &CampaignCategory::BencanaAlam,
+        &100_000_000,
+        &recipient,
+    );
+
+    // Anyone can bump before the TTL runs out
+    env.ledger().with_mut(|l| l.sequence_number += 500_000);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get_ttl(), 100_000);
+    });
+    client.bump_campaign(&campaign_id);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get_ttl(), 600_000);
+    });
+
+    // Past the original TTL, data is still live and readable
+    env.ledger().with_mut(|l| l.sequence_number += 550_000);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get_ttl(), 50_000);
+    });
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Extend-to must be at least the threshold")]
+fn test_set_ttl_config_invalid() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+    client.set_ttl_config(&admin, &1_000, &100);
+}
+
+#[test]
+fn test_donation_limits() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Beasiswa"),
+        &String::from_str(&env, "Beasiswa anak yatim"),
+        &CampaignCategory::Pendidikan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.set_donation_limits(&admin, &campaign_id, &1_000_000, &10_000_000);
+
+    let campaign = client.get_campaign(&campaign_id);
+    assert_eq!(campaign.min_donation, 1_000_000);
+    assert_eq!(campaign.max_donation, 10_000_000);
+
+    assert_eq!(
+        client.try_donate(&donor, &campaign_id, &999_999, &false),
+        Err(Ok(Error::BelowMinimum.into()))
+    );
+    assert_eq!(
+        client.try_donate(&donor, &campaign_id, &10_000_001, &false),
+        Err(Ok(Error::AboveMaximum.into()))
+    );
+
+    client.donate(&donor, &campaign_id, &1_000_000, &false);
+    client.donate(&donor, &campaign_id, &10_000_000, &false);
+    assert_eq!(client.get_campaign(&campaign_id).current_amount, 11_000_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_set_donation_limits_invalid() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.set_donation_limits(&admin, &campaign_id, &-1, &0);
+}
+
+// Property tests feed adversarial amounts through the full contract
+fn setup_open_campaign(env: &Env) -> (ZakatContractClient<'_>, Address, u32) {
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let recipient = Address::generate(env);
+    let donor = Address::generate(env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    // Target of i128::MAX keeps the campaign active for any sum that fits
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(env, "Test Campaign"),
+        &String::from_str(env, "Description"),
+        &CampaignCategory::Zakat,
+        &i128::MAX,
+        &recipient,
+    );
+
+    (client, donor, campaign_id)
+}
+
+fn prop_env() -> Env {
+    Env::new_with_config(EnvTestConfig {
+        capture_snapshot_at_drop: false,
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn prop_non_positive_donation_rejected(amount in prop_oneof![Just(i128::MIN), Just(0i128), i128::MIN..=0]) {
+        let env = prop_env();
+        let (client, donor, campaign_id) = setup_open_campaign(&env);
+
+        prop_assert_eq!(
+            client.try_donate(&donor, &campaign_id, &amount, &false),
+            Err(Ok(Error::InvalidAmount.into()))
+        );
+        prop_assert_eq!(client.get_campaign(&campaign_id).current_amount, 0);
+        prop_assert_eq!(client.get_all_donations(&0, &100).len(), 0);
+    }
+
+    #[test]
+    fn prop_donation_sum_never_wraps(
+        first in prop_oneof![Just(i128::MAX - 1), Just(1i128), 1..i128::MAX],
+        second in prop_oneof![Just(i128::MAX), Just(1i128), 1..=i128::MAX],
+    ) {
+        let env = prop_env();
+        let (client, donor, campaign_id) = setup_open_campaign(&env);
+
+        client.donate(&donor, &campaign_id, &first, &false);
+        let result = client.try_donate(&donor, &campaign_id, &second, &false);
+
+        match first.checked_add(second) {
+            Some(sum) => {
+                prop_assert!(result.is_ok());
+                prop_assert_eq!(client.get_campaign(&campaign_id).current_amount, sum);
+                prop_assert_eq!(client.get_donor_total(&donor), sum);
+            }
+            None => {
+                prop_assert_eq!(result, Err(Ok(Error::Overflow.into())));
+                prop_assert_eq!(client.get_campaign(&campaign_id).current_amount, first);
+                prop_assert_eq!(client.get_all_donations(&0, &100).len(), 1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_verify_integrity() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+
+    client.initialize(&admin);
+
+    let campaign_id = client.create_campaign(
+        &admin,
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Kesehatan,
+        &50_000_000,
+        &recipient,
+    );
+
+    client.donate(&donor, &campaign_id, &20_000_000, &false);
+    client.donate(&donor, &campaign_id, &30_000_000, &true);
+
+    let report = client.verify_integrity(&campaign_id);
+    assert_eq!(report.donation_total, 50_000_000);
+    assert_eq!(report.current_amount, 50_000_000);
+    assert_eq!(report.withdrawn_amount, 0);
+    assert!(report.is_consistent);
+
+    // Withdrawn funds still count towards the donation total
+    client.withdraw(&recipient, &campaign_id);
+    let report = client.verify_integrity(&campaign_id);
+    assert_eq!(report.current_amount, 0);
+    assert_eq!(report.withdrawn_amount, 50_000_000);
+    assert!(report.is_consistent);
+}
+
+// Randomized operation sequences, checking integrity after every step
+#[derive(Clone, Debug)]
+enum Op {
+    Create(i128, u8),
+    Donate(u32, usize, i128, bool),
+    Update(u32, i128),
+    Close(u32),
+    Withdraw(u32),
+    SetPolicy(u32, u8, u32),
+    Redirect(u32, u32, i128),
+    DonateToPool(usize, bool, i128),
+    Allocate(u32, i128),
+    AddUnit(u32, bool),
+    JoinQurban(u32, usize, u32),
+    SetLoan(u32, bool),
+    Repay(u32, i128),
+    Schedule(u32, i128),
+    Claim(u32, u32),
+    SetVendorOnly(u32),
+    PayVendor(u32, i128, u8),
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    let amount = prop_oneof![
+        Just(0i128),
+        Just(-1i128),
+        Just(i128::MAX),
+        1i128..100_000_000,
+    ];
+    prop_oneof![
+        1 => (1i128..200_000_000, 0u8..3).prop_map(|(t, c)| Op::Create(t, c)),
+        4 => (0u32..4, 0usize..3, amount, any::<bool>())
+            .prop_map(|(c, d, a, anon)| Op::Donate(c, d, a, anon)),
+        1 => (0u32..4, 1i128..300_000_000).prop_map(|(c, t)| Op::Update(c, t)),
+        1 => (0u32..4).prop_map(Op::Close),
+        1 => (0u32..4).prop_map(Op::Withdraw),
+        1 => (0u32..4, 0u8..4, 0u32..4).prop_map(|(c, p, t)| Op::SetPolicy(c, p, t)),
+        1 => (0u32..4, 0u32..4, 1i128..100_000_000).prop_map(|(f, t, a)| Op::Redirect(f, t, a)),
+        1 => (0usize..3, any::<bool>(), 1i128..100_000_000)
+            .prop_map(|(d, zakat, a)| Op::DonateToPool(d, zakat, a)),
+        1 => (0u32..4, 1i128..100_000_000).prop_map(|(c, a)| Op::Allocate(c, a)),
+        // prop_oneof! takes at most 10 arms, so mode ops are grouped
+        3 => prop_oneof![
+            1 => (0u32..4, any::<bool>()).prop_map(|(c, cow)| Op::AddUnit(c, cow)),
+            2 => (0u32..4, 0usize..3, 0u32..3).prop_map(|(c, d, u)| Op::JoinQurban(c, d, u)),
+            1 => (0u32..4, any::<bool>()).prop_map(|(c, revolving)| Op::SetLoan(c, revolving)),
+            2 => (0u32..4, 1i128..100_000_000).prop_map(|(c, a)| Op::Repay(c, a)),
+            1 => (0u32..4, 1i128..100_000_000).prop_map(|(c, a)| Op::Schedule(c, a)),
+            2 => (0u32..4, 0u32..3).prop_map(|(c, i)| Op::Claim(c, i)),
+            1 => (0u32..4).prop_map(Op::SetVendorOnly),
+            2 => (0u32..4, 1i128..100_000_000, 0u8..4).prop_map(|(c, a, i)| Op::PayVendor(c, a, i)),
+        ],
+    ]
+}
+
+// Category of a campaign created during the run
+fn campaign_category(category: u8) -> CampaignCategory {
+    match category {
+        0 => CampaignCategory::UMKM,
+        1 => CampaignCategory::Pendidikan,
+        _ => CampaignCategory::Kesehatan,
+    }
+}
+
+// Excess policy picked by index, redirecting to another existing campaign
+fn excess_policy(policy: u8, target: u32) -> ExcessPolicy {
+    match policy {
+        0 => ExcessPolicy::AcceptAll,
+        1 => ExcessPolicy::RejectExcess,
+        2 => ExcessPolicy::RefundExcess,
+        _ => ExcessPolicy::RedirectExcess(target),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn prop_integrity_holds_across_operations(
+        ops in prop::collection::vec(op_strategy(), 1..20),
+        custody in any::<bool>(),
+    ) {
+        let env = prop_env();
+        let contract_id = env.register(ZakatContract, ());
+        let client = ZakatContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let donors = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+
+        env.mock_all_auths();
+
+        client.initialize(&admin);
+        let vendor = Address::generate(&env);
+        client.set_vendor(&admin, &vendor, &true);
+
+        // Half the runs hold real tokens, so integrity also checks custody
+        if custody {
+            let asset = env.register_stellar_asset_contract_v2(admin.clone());
+            let asset_admin = token::StellarAssetClient::new(&env, &asset.address());
+            for donor in donors.iter() {
+                asset_admin.mint(donor, &i128::MAX);
+            }
+            client.set_token(&admin, &asset.address());
+        }
+
+        let title = String::from_str(&env, "Campaign");
+        let description = String::from_str(&env, "Description");
+        client.create_campaign(&admin, &title, &description, &CampaignCategory::Zakat, &100_000_000, &recipient);
+
+        for op in ops {
+            // Campaign indices wrap onto the campaigns that exist so far
+            let count = client.get_campaigns().len();
+            let id = |c: u32| c % count + 1;
+
+            // Failed calls roll back, so only the state after each call matters
+            let _ = match op {
+                Op::Create(target, category) => client
+                    .try_create_campaign(&admin, &title, &description, &campaign_category(category), &target, &recipient)
+                    .map(|_| ()),
+                Op::Donate(c, d, amount, anon) => client
+                    .try_donate(&donors[d], &id(c), &amount, &anon)
+                    .map(|_| ()),
+                Op::Update(c, target) => client
+                    .try_update_campaign(&admin, &id(c), &title, &description, &target)
+                    .map(|_| ()),
+                Op::Close(c) => client.try_close_campaign(&admin, &id(c)).map(|_| ()),
+                Op::Withdraw(c) => client.try_withdraw(&recipient, &id(c)).map(|_| ()),
+                Op::SetPolicy(c, policy, t) => client
+                    .try_set_excess_policy(&admin, &id(c), &excess_policy(policy, id(t)))
+                    .map(|_| ()),
+                Op::Redirect(f, t, amount) => {
+                    // Announce, wait out the grace period, then move
+                    let _ = client.try_announce_redirect(&admin, &id(f), &id(t), &amount);
+                    env.ledger().with_mut(|l| l.timestamp += 30 * 24 * 60 * 60);
+                    client.try_redirect_funds(&admin, &id(f), &id(t), &amount).map(|_| ())
+                }
+                Op::DonateToPool(d, zakat, amount) => {
+                    let category = if zakat { CampaignCategory::Zakat } else { CampaignCategory::UMKM };
+                    client.try_donate_to_category(&donors[d], &category, &amount).map(|_| ())
+                }
+                Op::Allocate(c, amount) => {
+                    let category = client.get_campaign(&id(c)).category;
+                    client
+                        .try_allocate_from_pool(&admin, &category, &id(c), &amount)
+                        .map(|_| ())
+                }
+                Op::AddUnit(c, cow) => {
+                    let (animal, price, slots) = if cow { ("Sapi", 7_000_000, 7) } else { ("Kambing", 3_000_000, 1) };
+                    client
+                        .try_add_qurban_unit(&admin, &id(c), &String::from_str(&env, animal), &price, &slots)
+                        .map(|_| ())
+                }
+                Op::JoinQurban(c, d, unit) => client
+                    .try_join_qurban(&donors[d], &id(c), &unit)
+                    .map(|_| ()),
+                Op::SetLoan(c, revolving) => {
+                    let mode = if revolving { RepaymentMode::Revolving } else { RepaymentMode::ReturnToDonors };
+                    client.try_set_loan_terms(&admin, &id(c), &mode, &2, &1_000).map(|_| ())
+                }
+                Op::Repay(c, amount) => client.try_repay(&recipient, &id(c), &amount).map(|_| ()),
+                Op::Schedule(c, amount) => {
+                    // The admin attests enrollment, installments are due at once
+                    let _ = client.try_set_scholarship_verifier(&admin, &id(c), &admin);
+                    client.try_add_installment(&admin, &id(c), &recipient, &amount, &0).map(|_| ())
+                }
+                Op::Claim(c, installment) => {
+                    let _ = client.try_attest_enrollment(&admin, &id(c), &installment);
+                    client.try_claim_installment(&recipient, &id(c), &installment).map(|_| ())
+                }
+                Op::SetVendorOnly(c) => client.try_set_vendor_only(&admin, &id(c), &true).map(|_| ()),
+                Op::PayVendor(c, amount, invoice) => {
+                    let invoice = BytesN::from_array(&env, &[invoice; 32]);
+                    client
+                        .try_pay_vendor(&recipient, &id(c), &vendor, &amount, &invoice)
+                        .map(|_| ())
+                }
+            };
+
+            let mut balance_total = Some(0i128);
+            let mut invoices = std::vec::Vec::new();
+            for campaign in client.get_campaigns().iter() {
+                // An invoice is paid at most once across all campaigns
+                for payment in client.get_vendor_payments(&campaign.id).iter() {
+                    prop_assert!(!invoices.contains(&payment.invoice_hash));
+                    invoices.push(payment.invoice_hash);
+                }
+                if let Some(loan) = client.get_loan(&campaign.id) {
+                    prop_assert!(loan.repaid <= loan.principal, "{:?}", loan);
+                }
+                // Every claimed installment was paid out of the campaign
+                let claimed: i128 = client
+                    .get_installments(&campaign.id)
+                    .iter()
+                    .filter(|i| i.claimed)
+                    .map(|i| i.amount)
+                    .sum();
+                prop_assert!(campaign.withdrawn_amount >= claimed);
+                let report = client.verify_integrity(&campaign.id);
+                prop_assert!(report.is_consistent, "{:?}", report);
+                balance_total = balance_total.and_then(|t| t.checked_add(report.current_amount));
+            }
+            if custody {
+                prop_assert!(client.verify_custody().is_consistent);
+            }
+
+            // Pool balances match what their donations have left unallocated
+            for category in [CampaignCategory::Zakat, CampaignCategory::UMKM] {
+                let mut unallocated = 0i128;
+                for donation in client.get_pool_donations(&category, &0, &u32::MAX).iter() {
+                    prop_assert!(donation.allocated <= donation.amount, "{:?}", donation);
+                    unallocated += donation.amount - donation.allocated;
+                }
+                prop_assert_eq!(client.get_pool_balance(&category), unallocated);
+            }
+
+            // Returned qurban shares and repayments never exceed what a donor gave
+            for donor in donors.iter() {
+                // Totals past i128 report overflow instead
+                if let Ok(Ok(total)) = client.try_get_donor_total(donor) {
+                    prop_assert!(total >= 0);
+                }
+            }
+
+            // The global total reports overflow rather than wrapping
+            match balance_total {
+                Some(total) => prop_assert_eq!(client.get_total_donations(), total),
+                None => prop_assert_eq!(
+                    client.try_get_total_donations(),
+                    Err(Ok(Error::Overflow.into()))
+                ),
+            }
+        }
+    }
+}
@@ -1,43 +1,45 @@
 #![no_std]
 
+use donation_core::{
+    checked_add, extend_instance_ttl, load_campaign, load_campaigns, record_revision,
+    save_campaign, CampaignUpdated, CAMPAIGN_COUNT,
+};
 use soroban_sdk::{
     contract, contractimpl, contracttype, Env, Address, Symbol, symbol_short, Vec, Map, String,
 };
 
-#[derive(Clone)]
-#[contracttype]
-pub enum CampaignStatus {
-    Active,
-    Completed,
-    Closed,
-}
+pub use donation_core::{
+    Campaign, CampaignCategory, CampaignRevision, CampaignStatus, Donation, Error,
+    ExcessPolicy, GoalType, IntegrityReport, TtlConfig,
+};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
-pub struct Campaign {
-    pub id: u32,
-    pub title: String,
-    pub description: String,
-    pub category: String,
-    pub target_amount: i128,
-    pub current_amount: i128,
-    pub recipient: Address,
-    pub status: CampaignStatus,
-    pub created_at: u64,
+pub struct RegionTotal {
+    pub region: String,
+    pub total_raised: i128,
+    pub active_campaigns: u32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
-pub struct Donation {
-    pub donor: Address,
-    pub amount: i128,
+pub struct Rejection {
+    pub reviewer: Address,
+    pub reason: String,
     pub timestamp: u64,
 }
 
 // 🔹 Keys untuk penyimpanan
-const CAMPAIGNS: Symbol = symbol_short!("CAMPAIGNS");
 const DONATIONS: Symbol = symbol_short!("DONATIONS");
-const CAMPAIGN_COUNT: Symbol = symbol_short!("COUNT");
+const ADMIN: Symbol = symbol_short!("ADMIN");
+const REVIEWERS: Symbol = symbol_short!("REVIEWERS");
+const REJECTIONS: Symbol = symbol_short!("REJECTS");
+const RESPONDERS: Symbol = symbol_short!("RESPONDRS");
+const EMERGENCY: Symbol = symbol_short!("EMERGENCY");
+const EMERGENCY_DURATION: Symbol = symbol_short!("EMRGDUR");
+
+// Lama mode darurat sebelum campaign wajib direview (3 hari)
+const DEFAULT_EMERGENCY_DURATION: u64 = 3 * 24 * 60 * 60;
 
 #[contract]
 pub struct ZakatContract;
@@ -45,13 +47,362 @@ pub struct ZakatContract;
 #[contractimpl]
 impl ZakatContract {
     // ----------------------------------------------------
-    // Inisialisasi kontrak (set counter awal)
+    // Inisialisasi kontrak (set counter awal dan admin)
     // ----------------------------------------------------
-    pub fn initialize(env: Env) {
+    pub fn initialize(env: Env, admin: Address) {
         if env.storage().instance().has(&CAMPAIGN_COUNT) {
             panic!("Already initialized");
         }
+        admin.require_auth();
+
+        env.storage().instance().set(&ADMIN, &admin);
         env.storage().instance().set(&CAMPAIGN_COUNT, &0u32);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan alamat admin
+    // ----------------------------------------------------
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&ADMIN).unwrap_or_else(|| panic!("Not initialized"))
+    }
+
+    // ----------------------------------------------------
+    // Mengatur batas TTL penyimpanan (hanya admin)
+    // ----------------------------------------------------
+    pub fn set_ttl_config(env: Env, admin: Address, threshold: u32, extend_to: u32) {
+        Self::require_admin(&env, &admin);
+
+        donation_core::set_ttl_config(&env, threshold, extend_to);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan batas TTL penyimpanan
+    // ----------------------------------------------------
+    pub fn get_ttl_config(env: Env) -> TtlConfig {
+        donation_core::get_ttl_config(&env)
+    }
+
+    // ----------------------------------------------------
+    // Memperpanjang umur data campaign (siapa saja boleh)
+    // ----------------------------------------------------
+    pub fn bump_campaign(env: Env, id: u32) {
+        let campaign = load_campaign(&env, id);
+
+        if let CampaignStatus::Closed = campaign.status {
+            panic!("Campaign already closed");
+        }
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Menambah reviewer (hanya admin)
+    // ----------------------------------------------------
+    pub fn add_reviewer(env: Env, admin: Address, reviewer: Address) {
+        Self::require_admin(&env, &admin);
+
+        let mut reviewers: Map<Address, bool> =
+            env.storage().instance().get(&REVIEWERS).unwrap_or(Map::new(&env));
+
+        reviewers.set(reviewer, true);
+        env.storage().instance().set(&REVIEWERS, &reviewers);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Menghapus reviewer (hanya admin)
+    // ----------------------------------------------------
+    pub fn remove_reviewer(env: Env, admin: Address, reviewer: Address) {
+        Self::require_admin(&env, &admin);
+
+        let mut reviewers: Map<Address, bool> =
+            env.storage().instance().get(&REVIEWERS).unwrap_or(Map::new(&env));
+
+        reviewers.remove(reviewer);
+        env.storage().instance().set(&REVIEWERS, &reviewers);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Cek apakah alamat adalah reviewer (admin selalu reviewer)
+    // ----------------------------------------------------
+    pub fn is_reviewer(env: Env, address: Address) -> bool {
+        if env.storage().instance().get::<_, Address>(&ADMIN) == Some(address.clone()) {
+            return true;
+        }
+
+        let reviewers: Map<Address, bool> =
+            env.storage().instance().get(&REVIEWERS).unwrap_or(Map::new(&env));
+
+        reviewers.get(address).unwrap_or(false)
+    }
+
+    // ----------------------------------------------------
+    // Menambah responder bencana (hanya admin)
+    // ----------------------------------------------------
+    pub fn add_responder(env: Env, admin: Address, responder: Address) {
+        Self::require_admin(&env, &admin);
+
+        let mut responders: Map<Address, bool> =
+            env.storage().instance().get(&RESPONDERS).unwrap_or(Map::new(&env));
+
+        responders.set(responder, true);
+        env.storage().instance().set(&RESPONDERS, &responders);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Menghapus responder bencana (hanya admin)
+    // ----------------------------------------------------
+    pub fn remove_responder(env: Env, admin: Address, responder: Address) {
+        Self::require_admin(&env, &admin);
+
+        let mut responders: Map<Address, bool> =
+            env.storage().instance().get(&RESPONDERS).unwrap_or(Map::new(&env));
+
+        responders.remove(responder);
+        env.storage().instance().set(&RESPONDERS, &responders);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Cek apakah alamat adalah responder
+    // ----------------------------------------------------
+    pub fn is_responder(env: Env, address: Address) -> bool {
+        let responders: Map<Address, bool> =
+            env.storage().instance().get(&RESPONDERS).unwrap_or(Map::new(&env));
+
+        responders.get(address).unwrap_or(false)
+    }
+
+    // ----------------------------------------------------
+    // Mengatur lama mode darurat dalam detik (hanya admin)
+    // ----------------------------------------------------
+    pub fn set_emergency_duration(env: Env, admin: Address, duration: u64) {
+        Self::require_admin(&env, &admin);
+
+        if duration == 0 {
+            panic!("Emergency duration must be positive");
+        }
+        env.storage().instance().set(&EMERGENCY_DURATION, &duration);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan lama mode darurat
+    // ----------------------------------------------------
+    pub fn get_emergency_duration(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&EMERGENCY_DURATION)
+            .unwrap_or(DEFAULT_EMERGENCY_DURATION)
+    }
+
+    // ----------------------------------------------------
+    // Membuat campaign bencana alam tanpa review (oleh responder).
+    // Setelah mode darurat berakhir, donasi baru butuh review.
+    // ----------------------------------------------------
+    pub fn create_emergency_campaign(
+        env: Env,
+        responder: Address,
+        title: String,
+        description: String,
+        target_amount: i128,
+        recipient: Address,
+        region: String,
+    ) -> u32 {
+        responder.require_auth();
+
+        if !Self::is_responder(env.clone(), responder.clone()) {
+            panic!("Only responder can create emergency campaigns");
+        }
+        if region.is_empty() {
+            panic!("Region is required");
+        }
+
+        donation_core::validate_target(&env, target_amount);
+
+        let count = donation_core::next_campaign_id(&env);
+
+        let campaign = Campaign {
+            id: count,
+            title,
+            description,
+            category: CampaignCategory::BencanaAlam,
+            region,
+            target_amount,
+            current_amount: 0,
+            withdrawn_amount: 0,
+            redirected_in: 0,
+            redirected_out: 0,
+            refunded_amount: 0,
+            recipient,
+            status: CampaignStatus::Active,
+            created_at: env.ledger().timestamp(),
+            min_donation: 0,
+            max_donation: 0,
+            excess_policy: ExcessPolicy::AcceptAll,
+            goal_type: GoalType::Fixed,
+        };
+
+        save_campaign(&env, &campaign);
+        record_revision(&env, &campaign, responder);
+
+        let mut emergencies: Map<u32, u64> =
+            env.storage().instance().get(&EMERGENCY).unwrap_or(Map::new(&env));
+
+        let expires_at = env
+            .ledger()
+            .timestamp()
+            .saturating_add(Self::get_emergency_duration(env.clone()));
+        emergencies.set(count, expires_at);
+        env.storage().instance().set(&EMERGENCY, &emergencies);
+
+        extend_instance_ttl(&env);
+
+        count
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan batas waktu mode darurat (None jika sudah direview)
+    // ----------------------------------------------------
+    pub fn get_emergency_expiry(env: Env, id: u32) -> Option<u64> {
+        let emergencies: Map<u32, u64> =
+            env.storage().instance().get(&EMERGENCY).unwrap_or(Map::new(&env));
+
+        emergencies.get(id)
+    }
+
+    // ----------------------------------------------------
+    // Mengesahkan campaign darurat setelah direview (oleh reviewer).
+    // Campaign yang sudah ditutup tetap bisa disahkan agar dananya
+    // bisa ditarik, kecuali yang ditolak.
+    // ----------------------------------------------------
+    pub fn confirm_emergency_campaign(env: Env, id: u32, reviewer: Address) {
+        Self::require_reviewer(&env, &reviewer);
+
+        let mut emergencies: Map<u32, u64> =
+            env.storage().instance().get(&EMERGENCY).unwrap_or(Map::new(&env));
+
+        if !emergencies.contains_key(id) {
+            panic!("Campaign is not in emergency mode");
+        }
+        if Self::get_rejection(env.clone(), id).is_some() {
+            panic!("Rejected campaigns cannot be confirmed");
+        }
+        emergencies.remove(id);
+        env.storage().instance().set(&EMERGENCY, &emergencies);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mengembalikan saldo campaign darurat yang ditutup tanpa
+    // disahkan (oleh reviewer). Mengembalikan jumlah yang di-refund.
+    // ----------------------------------------------------
+    pub fn refund_emergency_campaign(env: Env, id: u32, reviewer: Address) -> i128 {
+        Self::require_reviewer(&env, &reviewer);
+
+        let mut emergencies: Map<u32, u64> =
+            env.storage().instance().get(&EMERGENCY).unwrap_or(Map::new(&env));
+
+        if !emergencies.contains_key(id) {
+            panic!("Campaign is not in emergency mode");
+        }
+
+        let mut campaign = load_campaign(&env, id);
+        if campaign.status != CampaignStatus::Closed {
+            panic!("Campaign must be closed before refund");
+        }
+
+        let amount = campaign.current_amount;
+        if amount > 0 {
+            campaign.refund(&env, amount);
+            save_campaign(&env, &campaign);
+        }
+
+        emergencies.remove(id);
+        env.storage().instance().set(&EMERGENCY, &emergencies);
+
+        extend_instance_ttl(&env);
+
+        amount
+    }
+
+    // ----------------------------------------------------
+    // Mengatur kode wilayah campaign (oleh penerima)
+    // ----------------------------------------------------
+    pub fn set_region(env: Env, id: u32, caller: Address, region: String) {
+        caller.require_auth();
+
+        let mut campaign = load_campaign(&env, id);
+
+        if caller != campaign.recipient {
+            panic!("Only recipient can set the region");
+        }
+
+        // Wilayah ikut direview, jadi hanya bisa diubah sebelum diajukan
+        match campaign.status {
+            CampaignStatus::Draft | CampaignStatus::Rejected => {}
+            _ => panic!("Region can only be set before review"),
+        }
+
+        campaign.region = region;
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan campaign aktif di suatu wilayah
+    // ----------------------------------------------------
+    pub fn get_active_campaigns_by_region(env: Env, region: String) -> Vec<Campaign> {
+        let mut result = Vec::new(&env);
+        for (_id, camp) in load_campaigns(&env).iter() {
+            if camp.region == region
+                && camp.status == CampaignStatus::Active
+                && !Self::is_emergency_expired(&env, camp.id)
+            {
+                result.push_back(camp);
+            }
+        }
+
+        result
+    }
+
+    // ----------------------------------------------------
+    // Total dana terkumpul per wilayah (campaign tanpa wilayah dilewati)
+    // ----------------------------------------------------
+    pub fn get_region_totals(env: Env) -> Vec<RegionTotal> {
+        let mut totals: Map<String, RegionTotal> = Map::new(&env);
+        for (_id, camp) in load_campaigns(&env).iter() {
+            if camp.region.is_empty() {
+                continue;
+            }
+
+            let mut entry = totals.get(camp.region.clone()).unwrap_or(RegionTotal {
+                region: camp.region.clone(),
+                total_raised: 0,
+                active_campaigns: 0,
+            });
+            let raised = checked_add(&env, camp.current_amount, camp.withdrawn_amount);
+            entry.total_raised = checked_add(&env, entry.total_raised, raised);
+            if camp.status == CampaignStatus::Active {
+                entry.active_campaigns += 1;
+            }
+            totals.set(camp.region.clone(), entry);
+        }
+
+        totals.values()
     }
 
     // ----------------------------------------------------
@@ -61,42 +412,195 @@ impl ZakatContract {
         env: Env,
         title: String,
         description: String,
-        category: String,
+        category: CampaignCategory,
         target_amount: i128,
         recipient: Address,
     ) -> u32 {
         recipient.require_auth();
 
-        let mut count: u32 = env
-            .storage()
-            .instance()
-            .get(&CAMPAIGN_COUNT)
-            .unwrap_or(0u32);
+        donation_core::validate_target(&env, target_amount);
 
-        count += 1;
+        let count = donation_core::next_campaign_id(&env);
 
         let campaign = Campaign {
             id: count,
             title,
             description,
             category,
+            region: String::from_str(&env, ""),
             target_amount,
             current_amount: 0,
+            withdrawn_amount: 0,
+            redirected_in: 0,
+            redirected_out: 0,
+            refunded_amount: 0,
             recipient: recipient.clone(),
-            status: CampaignStatus::Active,
+            status: CampaignStatus::Draft,
             created_at: env.ledger().timestamp(),
+            min_donation: 0,
+            max_donation: 0,
+            excess_policy: ExcessPolicy::AcceptAll,
+            goal_type: GoalType::Fixed,
         };
 
-        let mut campaigns: Map<u32, Campaign> =
-            env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
+        // Simpan campaign dan versi awal ke riwayat
+        save_campaign(&env, &campaign);
+        record_revision(&env, &campaign, recipient);
 
-        campaigns.set(count, campaign.clone());
-        env.storage().instance().set(&CAMPAIGNS, &campaigns);
-        env.storage().instance().set(&CAMPAIGN_COUNT, &count);
+        extend_instance_ttl(&env);
 
         count
     }
 
+    // ----------------------------------------------------
+    // Mengubah judul, deskripsi, dan target (oleh penerima)
+    // ----------------------------------------------------
+    pub fn update_campaign(
+        env: Env,
+        id: u32,
+        caller: Address,
+        title: String,
+        description: String,
+        target_amount: i128,
+    ) -> u32 {
+        caller.require_auth();
+
+        let mut campaign = load_campaign(&env, id);
+
+        if caller != campaign.recipient {
+            panic!("Only recipient can update the campaign");
+        }
+
+        // Teks boleh diubah kapan saja, target hanya sebelum/selama aktif
+        campaign.validate_update(&env, target_amount);
+
+        campaign.title = title;
+        campaign.description = description;
+        campaign.target_amount = target_amount;
+
+        // Simpan dan tambahkan versi baru ke riwayat
+        save_campaign(&env, &campaign);
+        let version = record_revision(&env, &campaign, caller);
+
+        CampaignUpdated {
+            campaign_id: id,
+            version,
+            target_amount,
+        }
+        .publish(&env);
+
+        extend_instance_ttl(&env);
+
+        version
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan riwayat perubahan campaign
+    // ----------------------------------------------------
+    pub fn get_campaign_history(env: Env, id: u32) -> Vec<CampaignRevision> {
+        donation_core::load_history(&env, id)
+    }
+
+    // ----------------------------------------------------
+    // Mengajukan campaign untuk direview (oleh penerima)
+    // ----------------------------------------------------
+    pub fn submit_campaign(env: Env, id: u32, recipient: Address) {
+        recipient.require_auth();
+
+        let mut campaign = load_campaign(&env, id);
+
+        if recipient != campaign.recipient {
+            panic!("Only recipient can submit the campaign");
+        }
+
+        match campaign.status {
+            CampaignStatus::Draft | CampaignStatus::Rejected => {
+                campaign.transition(CampaignStatus::PendingReview);
+            }
+            _ => panic!("Campaign is not a draft"),
+        }
+
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Menyetujui campaign (oleh reviewer)
+    // ----------------------------------------------------
+    pub fn approve_campaign(env: Env, id: u32, reviewer: Address) {
+        Self::require_reviewer(&env, &reviewer);
+
+        let mut campaign = load_campaign(&env, id);
+
+        if campaign.status != CampaignStatus::PendingReview {
+            panic!("Campaign is not pending review");
+        }
+        campaign.transition(CampaignStatus::Active);
+
+        save_campaign(&env, &campaign);
+
+        // Hapus alasan penolakan sebelumnya jika ada
+        let mut rejections: Map<u32, Rejection> =
+            env.storage().instance().get(&REJECTIONS).unwrap_or(Map::new(&env));
+
+        if rejections.contains_key(id) {
+            rejections.remove(id);
+            env.storage().instance().set(&REJECTIONS, &rejections);
+        }
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Menolak campaign beserta alasannya (oleh reviewer)
+    // ----------------------------------------------------
+    pub fn reject_campaign(env: Env, id: u32, reviewer: Address, reason: String) {
+        Self::require_reviewer(&env, &reviewer);
+
+        if reason.is_empty() {
+            panic!("Rejection reason is required");
+        }
+
+        let mut campaign = load_campaign(&env, id);
+
+        // Campaign darurat yang kedaluwarsa ditolak dengan ditutup
+        if Self::is_emergency_expired(&env, id) && campaign.status.is_live() {
+            campaign.transition(CampaignStatus::Closed);
+        } else if campaign.status != CampaignStatus::PendingReview {
+            panic!("Campaign is not pending review");
+        } else {
+            campaign.transition(CampaignStatus::Rejected);
+        }
+
+        save_campaign(&env, &campaign);
+
+        let mut rejections: Map<u32, Rejection> =
+            env.storage().instance().get(&REJECTIONS).unwrap_or(Map::new(&env));
+
+        rejections.set(
+            id,
+            Rejection {
+                reviewer,
+                reason,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+        env.storage().instance().set(&REJECTIONS, &rejections);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Mendapatkan alasan penolakan terakhir sebuah campaign
+    // ----------------------------------------------------
+    pub fn get_rejection(env: Env, id: u32) -> Option<Rejection> {
+        let rejections: Map<u32, Rejection> =
+            env.storage().instance().get(&REJECTIONS).unwrap_or(Map::new(&env));
+
+        rejections.get(id)
+    }
+
 
     // ----------------------------------------------------
     // Mendapatkan total semua donasi dari semua campaign
@@ -108,7 +612,7 @@ impl ZakatContract {
         let mut total: i128 = 0;
         for (_id, list) in donations.iter() {
             for donation in list.iter() {
-                total += donation.amount;
+                total = checked_add(&env, total, donation.amount);
             }
         }
 
@@ -119,8 +623,7 @@ impl ZakatContract {
     // Mendapatkan semua campaign
     // ----------------------------------------------------
     pub fn get_campaigns(env: Env) -> Vec<Campaign> {
-        let campaigns: Map<u32, Campaign> =
-            env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
+        let campaigns = load_campaigns(&env);
 
         let mut result = Vec::new(&env);
         for (_id, camp) in campaigns.iter() {
@@ -134,53 +637,22 @@ impl ZakatContract {
     // Mendapatkan campaign berdasarkan ID
     // ----------------------------------------------------
     pub fn get_campaign(env: Env, id: u32) -> Campaign {
-    let campaigns: Map<u32, Campaign> =
-        env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
-
-    match campaigns.get(id) {
-        Some(c) => c,
-        None => panic!("Campaign not found"),
+        load_campaign(&env, id)
     }
-}
 
 
     // ----------------------------------------------------
     // Donasi ke campaign tertentu
     // ----------------------------------------------------
-    pub fn donate(env: Env, id: u32, donor: Address, amount: i128) {
+    pub fn donate(env: Env, id: u32, donor: Address, amount: i128) -> i128 {
         donor.require_auth();
 
-        let mut campaigns: Map<u32, Campaign> =
-            env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
+        // Mengembalikan bagian yang di-refund (RefundExcess)
+        let refunded = Self::apply_donation(&env, id, &donor, amount, false);
 
-        let mut campaign = campaigns.get(id).unwrap_or_else(|| panic!("Campaign not found"));
-        if let CampaignStatus::Closed = campaign.status {
-            panic!("Campaign already closed");
-        }
+        extend_instance_ttl(&env);
 
-        campaign.current_amount += amount;
-
-        // Jika sudah mencapai target, ubah status ke Completed
-        if campaign.current_amount >= campaign.target_amount {
-            campaign.status = CampaignStatus::Completed;
-        }
-
-        campaigns.set(id, campaign.clone());
-        env.storage().instance().set(&CAMPAIGNS, &campaigns);
-
-        // Simpan data donasi
-        let mut donations: Map<u32, Vec<Donation>> =
-            env.storage().instance().get(&DONATIONS).unwrap_or(Map::new(&env));
-
-        let mut list = donations.get(id).unwrap_or(Vec::new(&env));
-        list.push_back(Donation {
-            donor,
-            amount,
-            timestamp: env.ledger().timestamp(),
-        });
-
-        donations.set(id, list);
-        env.storage().instance().set(&DONATIONS, &donations);
+        refunded
     }
 
     // ----------------------------------------------------
@@ -193,25 +665,105 @@ impl ZakatContract {
         donations.get(id).unwrap_or(Vec::new(&env))
     }
 
+    // ----------------------------------------------------
+    // Cek integritas: total donasi = saldo + dana yang ditarik
+    // ----------------------------------------------------
+    pub fn verify_integrity(env: Env, id: u32) -> IntegrityReport {
+        let campaign = load_campaign(&env, id);
+
+        let mut total: i128 = 0;
+        for donation in Self::get_donations(env.clone(), id).iter() {
+            total = checked_add(&env, total, donation.amount);
+        }
+
+        campaign.integrity_report(total)
+    }
+
     // ----------------------------------------------------
     // Menutup campaign (oleh admin atau penerima)
     // ----------------------------------------------------
     pub fn close_campaign(env: Env, id: u32, caller: Address) {
         caller.require_auth();
 
-        let mut campaigns: Map<u32, Campaign> =
-            env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
+        let mut campaign = load_campaign(&env, id);
+
+        // Reviewer juga boleh menutup campaign darurat yang kedaluwarsa
+        let reviewer_close =
+            Self::is_emergency_expired(&env, id) && Self::is_reviewer(env.clone(), caller.clone());
+        if caller != campaign.recipient && !reviewer_close {
+            panic!("Only recipient can close the campaign");
+        }
+
+        campaign.transition(CampaignStatus::Closed);
+
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Batas minimum & maksimum per donasi (0 = tanpa batas)
+    // ----------------------------------------------------
+    pub fn set_donation_limits(
+        env: Env,
+        id: u32,
+        caller: Address,
+        min_donation: i128,
+        max_donation: i128,
+    ) {
+        caller.require_auth();
 
-        let mut campaign = campaigns.get(id).unwrap_or_else(|| panic!("Campaign not found"));
+        let mut campaign = load_campaign(&env, id);
 
         if caller != campaign.recipient {
-            panic!("Only recipient can close the campaign");
+            panic!("Only recipient can set donation limits");
+        }
+
+        campaign.set_donation_limits(&env, min_donation, max_donation);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Kebijakan kelebihan donasi di atas target
+    // ----------------------------------------------------
+    pub fn set_excess_policy(env: Env, id: u32, caller: Address, policy: ExcessPolicy) {
+        caller.require_auth();
+
+        let mut campaign = load_campaign(&env, id);
+
+        if caller != campaign.recipient {
+            panic!("Only recipient can set excess policy");
+        }
+
+        // Campaign tujuan redirect harus ada
+        if let ExcessPolicy::RedirectExcess(target_id) = policy {
+            load_campaign(&env, target_id);
+        }
+
+        campaign.set_excess_policy(&env, policy);
+        save_campaign(&env, &campaign);
+
+        extend_instance_ttl(&env);
+    }
+
+    // ----------------------------------------------------
+    // Jenis target: tetap, fleksibel, atau terbuka
+    // ----------------------------------------------------
+    pub fn set_goal_type(env: Env, id: u32, caller: Address, goal_type: GoalType) {
+        caller.require_auth();
+
+        let mut campaign = load_campaign(&env, id);
+
+        if caller != campaign.recipient {
+            panic!("Only recipient can set goal type");
         }
 
-        campaign.status = CampaignStatus::Closed;
+        campaign.set_goal_type(&env, goal_type);
+        save_campaign(&env, &campaign);
 
-        campaigns.set(id, campaign);
-        env.storage().instance().set(&CAMPAIGNS, &campaigns);
+        extend_instance_ttl(&env);
     }
 
     // ----------------------------------------------------
@@ -220,24 +772,135 @@ impl ZakatContract {
     pub fn withdraw(env: Env, id: u32, caller: Address) -> i128 {
         caller.require_auth();
 
-        let mut campaigns: Map<u32, Campaign> =
-            env.storage().instance().get(&CAMPAIGNS).unwrap_or(Map::new(&env));
-
-        let mut campaign = campaigns.get(id).unwrap_or_else(|| panic!("Campaign not found"));
+        let mut campaign = load_campaign(&env, id);
 
         if caller != campaign.recipient {
             panic!("Only recipient can withdraw");
         }
 
+        // Dana campaign darurat baru bisa ditarik setelah direview
+        if Self::get_emergency_expiry(env.clone(), id).is_some() {
+            panic!("Emergency campaign needs review before withdrawal");
+        }
+
         match campaign.status {
-            CampaignStatus::Completed | CampaignStatus::Closed => {
-                let amount = campaign.current_amount;
-                campaign.current_amount = 0;
-                campaigns.set(id, campaign);
-                env.storage().instance().set(&CAMPAIGNS, &campaigns);
-                amount
-            }
+            CampaignStatus::Completed | CampaignStatus::Closed => {}
+            // Target fleksibel/terbuka boleh ditarik selama masih aktif
+            CampaignStatus::Active if campaign.allows_partial_withdrawal() => {}
             _ => panic!("Campaign not completed or closed yet"),
         }
+
+        let amount = campaign.withdraw_all(&env);
+        save_campaign(&env, &campaign);
+        extend_instance_ttl(&env);
+
+        amount
+    }
+
+    // ----------------------------------------------------
+    // Helper: catat donasi sesuai kebijakan kelebihan target.
+    // Kelebihan yang dialihkan tidak dicek batas donasi tujuan
+    // dan tidak dialihkan lagi (sisanya di-refund).
+    // ----------------------------------------------------
+    fn apply_donation(env: &Env, id: u32, donor: &Address, amount: i128, redirected: bool) -> i128 {
+        let mut campaign = load_campaign(env, id);
+        match campaign.status {
+            CampaignStatus::Closed => panic!("Campaign already closed"),
+            CampaignStatus::Draft | CampaignStatus::PendingReview | CampaignStatus::Rejected => {
+                panic!("Campaign has not been approved")
+            }
+            _ => {}
+        }
+
+        // Campaign darurat yang melewati batas waktu harus direview dulu
+        if Self::is_emergency_expired(env, id) {
+            panic!("Emergency period ended, campaign needs review");
+        }
+
+        // Nominal harus positif dan berada di dalam batas campaign
+        if redirected {
+            donation_core::validate_amount(env, amount);
+        } else {
+            campaign.validate_donation(env, amount);
+        }
+
+        let (accepted, excess) = campaign.split_excess(env, amount);
+
+        // Campaign yang sudah penuh tidak mencatat donasi kosong
+        if accepted > 0 {
+            campaign.add_funds(env, accepted);
+
+            // Jika target tetap sudah tercapai, ubah status ke Completed
+            if campaign.status == CampaignStatus::Active && campaign.target_reached() {
+                campaign.transition(CampaignStatus::Completed);
+            }
+
+            save_campaign(env, &campaign);
+
+            // Simpan data donasi
+            let mut donations: Map<u32, Vec<Donation>> =
+                env.storage().instance().get(&DONATIONS).unwrap_or(Map::new(env));
+
+            let mut list = donations.get(id).unwrap_or(Vec::new(env));
+            list.push_back(Donation {
+                campaign_id: id,
+                donor: donor.clone(),
+                amount: accepted,
+                timestamp: env.ledger().timestamp(),
+                is_anonymous: false,
+                batch_id: 0,
+            });
+
+            donations.set(id, list);
+            env.storage().instance().set(&DONATIONS, &donations);
+        }
+
+        // Tujuan yang sudah ditutup atau belum disetujui tidak menerima
+        // kelebihan; kelebihan di-refund seperti RefundExcess
+        match campaign.excess_policy {
+            ExcessPolicy::RedirectExcess(target_id)
+                if excess > 0 && !redirected && load_campaign(env, target_id).status.is_live() =>
+            {
+                Self::apply_donation(env, target_id, donor, excess, true)
+            }
+            _ => excess,
+        }
+    }
+
+    // ----------------------------------------------------
+    // Helper: campaign darurat yang belum direview dan sudah lewat batas
+    // ----------------------------------------------------
+    fn is_emergency_expired(env: &Env, id: u32) -> bool {
+        match Self::get_emergency_expiry(env.clone(), id) {
+            Some(expires_at) => env.ledger().timestamp() > expires_at,
+            None => false,
+        }
+    }
+
+    // ----------------------------------------------------
+    // Helper: verifikasi admin
+    // ----------------------------------------------------
+    fn require_admin(env: &Env, admin: &Address) {
+        admin.require_auth();
+
+        let stored_admin: Address =
+            env.storage().instance().get(&ADMIN).unwrap_or_else(|| panic!("Not initialized"));
+        if *admin != stored_admin {
+            panic!("Only admin can perform this action");
+        }
+    }
+
+    // ----------------------------------------------------
+    // Helper: verifikasi reviewer
+    // ----------------------------------------------------
+    fn require_reviewer(env: &Env, reviewer: &Address) {
+        reviewer.require_auth();
+
+        if !Self::is_reviewer(env.clone(), reviewer.clone()) {
+            panic!("Only reviewer can review campaigns");
+        }
     }
 }
+
+#[cfg(test)]
+mod test;
@@ -1,57 +1,71 @@
 #![cfg(test)]
+extern crate std;
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::{storage::Instance as _, Address as _, EnvTestConfig, Ledger},
+    Address, Env, String,
+};
+
+// Create a campaign as `recipient` and take it through review
+fn create_active_campaign(
+    env: &Env,
+    client: &ZakatContractClient,
+    admin: &Address,
+    recipient: &Address,
+    target_amount: i128,
+) -> u32 {
+    let id = client.create_campaign(
+        &String::from_str(env, "Zakat Fitrah"),
+        &String::from_str(env, "Zakat untuk fakir miskin"),
+        &CampaignCategory::Zakat,
+        &target_amount,
+        recipient,
+    );
+    client.submit_campaign(&id, recipient);
+    client.approve_campaign(&id, admin);
+
+    id
+}
 
 #[test]
 fn test_initialize() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    
+
     env.mock_all_auths();
     client.initialize(&admin);
 
     assert_eq!(client.get_admin(), admin);
+    assert!(client.is_reviewer(&admin));
+    assert_eq!(client.get_campaigns().len(), 0);
 }
 
 #[test]
-fn test_create_campaign_zakat() {
+#[should_panic(expected = "Already initialized")]
+fn test_initialize_twice() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
-    let recipient = Address::generate(&env);
+    let other = Address::generate(&env);
 
     env.mock_all_auths();
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Zakat Fitrah 2025"),
-        &String::from_str(&env, "Distribusi zakat untuk fakir miskin"),
-        &CampaignCategory::Zakat,
-        &50_000_000, // 5 XLM target
-        &recipient,
-    );
-
-    assert_eq!(campaign_id, 1);
-
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.id, 1);
-    assert_eq!(campaign.title, String::from_str(&env, "Zakat Fitrah 2025"));
-    assert_eq!(campaign.current_amount, 0);
-    assert_eq!(campaign.target_amount, 50_000_000);
-    assert_eq!(campaign.status, CampaignStatus::Active);
+    // Second initialize must not replace the admin
+    client.initialize(&other);
 }
 
 #[test]
-fn test_create_campaign_bencana_alam() {
+fn test_create_campaign_by_recipient() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -60,75 +74,154 @@ fn test_create_campaign_bencana_alam() {
     env.mock_all_auths();
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Bantu Korban Banjir Jakarta"),
+    let id = client.create_campaign(
+        &String::from_str(&env, "Bantu Korban Banjir"),
         &String::from_str(&env, "Donasi untuk korban banjir"),
         &CampaignCategory::BencanaAlam,
-        &100_000_000, // 10 XLM target
+        &100_000_000,
         &recipient,
     );
 
-    assert_eq!(campaign_id, 1);
+    assert_eq!(id, 1);
 
-    let campaign = client.get_campaign(&campaign_id);
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.title, String::from_str(&env, "Bantu Korban Banjir"));
     assert_eq!(campaign.category, CampaignCategory::BencanaAlam);
+    assert_eq!(campaign.recipient, recipient);
+    assert_eq!(campaign.current_amount, 0);
+    assert_eq!(campaign.status, CampaignStatus::Draft);
 }
 
 #[test]
 fn test_create_multiple_campaigns() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    let recipient1 = Address::generate(&env);
+    let recipient2 = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id1 = create_active_campaign(&env, &client, &admin, &recipient1, 50_000_000);
+    let id2 = create_active_campaign(&env, &client, &admin, &recipient2, 80_000_000);
+
+    assert_eq!(id1, 1);
+    assert_eq!(id2, 2);
+
+    let campaigns = client.get_campaigns();
+    assert_eq!(campaigns.len(), 2);
+    assert_eq!(campaigns.get(1).unwrap().recipient, recipient2);
+}
+
+#[test]
+fn test_approve_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let reviewer = Address::generate(&env);
     let recipient = Address::generate(&env);
 
     env.mock_all_auths();
     client.initialize(&admin);
+    client.add_reviewer(&admin, &reviewer);
+    assert!(client.is_reviewer(&reviewer));
 
-    // Create campaign 1
-    let id1 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 1"),
-        &String::from_str(&env, "Description 1"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+    let id = client.create_campaign(
+        &String::from_str(&env, "Beasiswa Yatim"),
+        &String::from_str(&env, "Dana pendidikan"),
+        &CampaignCategory::Pendidikan,
+        &100_000_000,
         &recipient,
     );
 
-    // Create campaign 2
-    let id2 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 2"),
-        &String::from_str(&env, "Description 2"),
-        &CampaignCategory::Pendidikan,
+    client.submit_campaign(&id, &recipient);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::PendingReview);
+
+    client.approve_campaign(&id, &reviewer);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Active);
+}
+
+#[test]
+fn test_reject_and_resubmit_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = client.create_campaign(
+        &String::from_str(&env, "Modal UMKM"),
+        &String::from_str(&env, "Bantuan modal"),
+        &CampaignCategory::UMKM,
         &100_000_000,
         &recipient,
     );
+    client.submit_campaign(&id, &recipient);
+    client.reject_campaign(&id, &admin, &String::from_str(&env, "Deskripsi kurang jelas"));
 
-    // Create campaign 3
-    let id3 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 3"),
-        &String::from_str(&env, "Description 3"),
-        &CampaignCategory::Kesehatan,
-        &150_000_000,
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Rejected);
+    let rejection = client.get_rejection(&id).unwrap();
+    assert_eq!(rejection.reviewer, admin);
+    assert_eq!(rejection.reason, String::from_str(&env, "Deskripsi kurang jelas"));
+
+    // Fix the description and resubmit
+    client.update_campaign(
+        &id,
         &recipient,
+        &String::from_str(&env, "Modal UMKM"),
+        &String::from_str(&env, "Bantuan modal usaha warung"),
+        &100_000_000,
     );
+    client.submit_campaign(&id, &recipient);
+    client.approve_campaign(&id, &admin);
 
-    assert_eq!(id1, 1);
-    assert_eq!(id2, 2);
-    assert_eq!(id3, 3);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Active);
+    assert_eq!(client.get_rejection(&id), None);
+}
 
-    let campaigns = client.get_campaigns();
-    assert_eq!(campaigns.len(), 3);
+#[test]
+#[should_panic(expected = "Only reviewer can review campaigns")]
+fn test_approve_campaign_not_reviewer() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let reviewer = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.add_reviewer(&admin, &reviewer);
+    client.remove_reviewer(&admin, &reviewer);
+
+    let id = client.create_campaign(
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
+        &CampaignCategory::Zakat,
+        &50_000_000,
+        &recipient,
+    );
+    client.submit_campaign(&id, &recipient);
+
+    // Removed reviewer can no longer approve
+    client.approve_campaign(&id, &reviewer);
 }
 
 #[test]
-fn test_donate_basic() {
+#[should_panic(expected = "Campaign has not been approved")]
+fn test_donate_to_pending_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -136,102 +229,133 @@ fn test_donate_basic() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Zakat Fitrah"),
-        &String::from_str(&env, "Zakat untuk fakir miskin"),
+    let id = client.create_campaign(
+        &String::from_str(&env, "Test Campaign"),
+        &String::from_str(&env, "Description"),
         &CampaignCategory::Zakat,
         &50_000_000,
         &recipient,
     );
+    client.submit_campaign(&id, &recipient);
 
-    // Donate 5 XLM
-    client.donate(&donor, &campaign_id, &50_000_000, &false);
+    client.donate(&id, &donor, &10_000_000);
+}
+
+#[test]
+fn test_donate_basic() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
 
-    let campaign = client.get_campaign(&campaign_id);
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor1 = Address::generate(&env);
+    let donor2 = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    client.donate(&id, &donor1, &20_000_000);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Active);
+
+    // Target reached
+    client.donate(&id, &donor2, &30_000_000);
+    let campaign = client.get_campaign(&id);
     assert_eq!(campaign.current_amount, 50_000_000);
-    assert_eq!(campaign.status, CampaignStatus::Completed); // Target reached
+    assert_eq!(campaign.status, CampaignStatus::Completed);
+
+    // Completed campaigns keep accepting donations until closed
+    client.donate(&id, &donor1, &5_000_000);
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.current_amount, 55_000_000);
+    assert_eq!(campaign.status, CampaignStatus::Completed);
 }
 
 #[test]
-fn test_donate_multiple_donors() {
+fn test_get_donations_by_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
     let donor1 = Address::generate(&env);
     let donor2 = Address::generate(&env);
-    let donor3 = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Beasiswa Anak Yatim"),
-        &String::from_str(&env, "Dana pendidikan"),
-        &CampaignCategory::Pendidikan,
-        &100_000_000, // 10 XLM target
-        &recipient,
-    );
+    let id1 = create_active_campaign(&env, &client, &admin, &recipient, 100_000_000);
+    let id2 = create_active_campaign(&env, &client, &admin, &recipient, 100_000_000);
+
+    client.donate(&id1, &donor1, &10_000_000);
+    client.donate(&id2, &donor2, &20_000_000);
+    client.donate(&id1, &donor2, &30_000_000);
 
-    // Multiple donations
-    client.donate(&donor1, &campaign_id, &30_000_000, &false); // 3 XLM
-    client.donate(&donor2, &campaign_id, &20_000_000, &true);  // 2 XLM (anonymous)
-    client.donate(&donor3, &campaign_id, &40_000_000, &false); // 4 XLM
+    let donations = client.get_donations(&id1);
+    assert_eq!(donations.len(), 2);
+    assert_eq!(donations.get(0).unwrap().donor, donor1);
+    assert_eq!(donations.get(0).unwrap().amount, 10_000_000);
+    assert_eq!(donations.get(1).unwrap().donor, donor2);
+    assert_eq!(donations.get(1).unwrap().campaign_id, id1);
+
+    let donations = client.get_donations(&id2);
+    assert_eq!(donations.len(), 1);
+    assert_eq!(donations.get(0).unwrap().amount, 20_000_000);
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.current_amount, 90_000_000); // 9 XLM total
-    assert_eq!(campaign.status, CampaignStatus::Active); // Not completed yet
+    // Unknown campaign has no donations
+    assert_eq!(client.get_donations(&99).len(), 0);
 
-    let donations = client.get_campaign_donations(&campaign_id);
-    assert_eq!(donations.len(), 3);
+    assert_eq!(client.get_total_donations(), 60_000_000);
 }
 
 #[test]
-fn test_donate_anonymous() {
+fn test_close_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Bantuan Kesehatan"),
-        &String::from_str(&env, "Bantuan obat-obatan"),
-        &CampaignCategory::Kesehatan,
-        &100_000_000,
-        &recipient,
-    );
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
 
-    // Anonymous donation
-    client.donate(&donor, &campaign_id, &30_000_000, &true);
+    client.close_campaign(&id, &recipient);
 
-    let donations = client.get_campaign_donations(&campaign_id);
-    assert_eq!(donations.len(), 1);
-    
-    let donation = donations.get(0).unwrap();
-    assert_eq!(donation.is_anonymous, true);
-    assert_eq!(donation.amount, 30_000_000);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Closed);
+}
+
+#[test]
+#[should_panic(expected = "Only recipient can close the campaign")]
+fn test_close_campaign_wrong_caller() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    client.close_campaign(&id, &stranger);
 }
 
 #[test]
-fn test_campaign_completion() {
+#[should_panic(expected = "Campaign already closed")]
+fn test_donate_to_closed_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -239,30 +363,45 @@ fn test_campaign_completion() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Modal UMKM"),
-        &String::from_str(&env, "Bantuan modal usaha"),
-        &CampaignCategory::UMKM,
-        &100_000_000, // 10 XLM target
-        &recipient,
-    );
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.close_campaign(&id, &recipient);
+
+    client.donate(&id, &donor, &10_000_000);
+}
+
+#[test]
+fn test_withdraw_completed_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
 
-    // Donate exactly target amount
-    client.donate(&donor, &campaign_id, &100_000_000, &false);
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.current_amount, 100_000_000);
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.donate(&id, &donor, &50_000_000);
+
+    let withdrawn = client.withdraw(&id, &recipient);
+    assert_eq!(withdrawn, 50_000_000);
+
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.current_amount, 0);
     assert_eq!(campaign.status, CampaignStatus::Completed);
+
+    // Donation history is kept after withdrawal
+    assert_eq!(client.get_donations(&id).len(), 1);
 }
 
 #[test]
-fn test_campaign_over_target() {
+fn test_withdraw_closed_campaign() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -270,73 +409,142 @@ fn test_campaign_over_target() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Emergency Fund"),
-        &String::from_str(&env, "Dana darurat"),
-        &CampaignCategory::BencanaAlam,
-        &50_000_000, // 5 XLM target
-        &recipient,
-    );
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.donate(&id, &donor, &20_000_000);
 
-    // Donate more than target
-    client.donate(&donor, &campaign_id, &70_000_000, &false); // 7 XLM
+    // Closing early lets the recipient withdraw what was raised
+    client.close_campaign(&id, &recipient);
+    assert_eq!(client.withdraw(&id, &recipient), 20_000_000);
+    assert_eq!(client.withdraw(&id, &recipient), 0);
+}
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.current_amount, 70_000_000);
-    assert_eq!(campaign.status, CampaignStatus::Completed); // Still completed
+#[test]
+#[should_panic(expected = "Campaign not completed or closed yet")]
+fn test_withdraw_active_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.donate(&id, &donor, &20_000_000);
+
+    client.withdraw(&id, &recipient);
 }
 
 #[test]
-fn test_get_all_donations() {
+#[should_panic(expected = "Only recipient can withdraw")]
+fn test_withdraw_wrong_caller() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let donor1 = Address::generate(&env);
-    let donor2 = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    // Create 2 campaigns
-    let campaign_id_1 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 1"),
-        &String::from_str(&env, "Description 1"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.donate(&id, &donor, &50_000_000);
+
+    client.withdraw(&id, &admin);
+}
+
+#[test]
+fn test_update_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    let version = client.update_campaign(
+        &id,
         &recipient,
+        &String::from_str(&env, "Zakat Fitrah 1446 H"),
+        &String::from_str(&env, "Zakat untuk fakir miskin"),
+        &75_000_000,
     );
+    assert_eq!(version, 1);
+    assert_eq!(client.get_campaign(&id).target_amount, 75_000_000);
+
+    let history = client.get_campaign_history(&id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap().target_amount, 50_000_000);
+    assert_eq!(history.get(1).unwrap().title, String::from_str(&env, "Zakat Fitrah 1446 H"));
+    assert_eq!(history.get(1).unwrap().editor, recipient);
+}
+
+#[test]
+#[should_panic(expected = "Only recipient can update the campaign")]
+fn test_update_campaign_wrong_caller() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
 
-    let campaign_id_2 = client.create_campaign(
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    client.update_campaign(
+        &id,
         &admin,
-        &String::from_str(&env, "Campaign 2"),
-        &String::from_str(&env, "Description 2"),
-        &CampaignCategory::Pendidikan,
-        &100_000_000,
-        &recipient,
+        &String::from_str(&env, "Changed"),
+        &String::from_str(&env, "Description"),
+        &50_000_000,
     );
+}
 
-    // Donate to both campaigns
-    client.donate(&donor1, &campaign_id_1, &20_000_000, &false);
-    client.donate(&donor2, &campaign_id_2, &30_000_000, &false);
-    client.donate(&donor1, &campaign_id_2, &15_000_000, &true);
+#[test]
+fn test_bump_campaign() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
 
-    let all_donations = client.get_all_donations();
-    assert_eq!(all_donations.len(), 3);
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.set_ttl_config(&admin, &600_000, &600_000);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    env.ledger().with_mut(|l| l.sequence_number += 500_000);
+    client.bump_campaign(&id);
+    env.as_contract(&contract_id, || {
+        assert_eq!(env.storage().instance().get_ttl(), 600_000);
+    });
+
+    env.ledger().with_mut(|l| l.sequence_number += 550_000);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Active);
 }
 
 #[test]
-fn test_get_total_donations() {
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_donate_negative_amount() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -344,80 +552,139 @@ fn test_get_total_donations() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    // Create 3 campaigns
-    let campaign_id_1 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 1"),
-        &String::from_str(&env, "Description 1"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
-        &recipient,
-    );
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
 
-    let campaign_id_2 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 2"),
-        &String::from_str(&env, "Description 2"),
-        &CampaignCategory::Pendidikan,
-        &100_000_000,
-        &recipient,
-    );
+    // A negative donation would otherwise drain the campaign balance
+    client.donate(&id, &donor, &-10_000_000);
+}
 
-    let campaign_id_3 = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Campaign 3"),
-        &String::from_str(&env, "Description 3"),
-        &CampaignCategory::Kesehatan,
-        &75_000_000,
-        &recipient,
-    );
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_create_campaign_zero_target() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
 
-    // Donate to all campaigns
-    client.donate(&donor, &campaign_id_1, &20_000_000, &false);
-    client.donate(&donor, &campaign_id_2, &30_000_000, &false);
-    client.donate(&donor, &campaign_id_3, &15_000_000, &false);
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
 
-    let total = client.get_total_donations();
-    assert_eq!(total, 65_000_000); // 6.5 XLM total
+    create_active_campaign(&env, &client, &admin, &recipient, 0);
 }
 
 #[test]
-fn test_close_campaign() {
+fn test_donation_limits() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
-        &recipient,
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_donation_limits(&id, &recipient, &2_500_000, &5_000_000);
+
+    assert_eq!(
+        client.try_donate(&id, &donor, &2_499_999),
+        Err(Ok(Error::BelowMinimum.into()))
+    );
+    assert_eq!(
+        client.try_donate(&id, &donor, &5_000_001),
+        Err(Ok(Error::AboveMaximum.into()))
     );
 
-    // Close campaign
-    client.close_campaign(&admin, &campaign_id);
+    client.donate(&id, &donor, &2_500_000);
+    assert_eq!(client.get_campaign(&id).current_amount, 2_500_000);
+}
+
+#[test]
+#[should_panic(expected = "Only recipient can set donation limits")]
+fn test_set_donation_limits_wrong_caller() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_donation_limits(&id, &stranger, &1, &0);
+}
 
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.status, CampaignStatus::Closed);
+// Adversarial amounts: extremes, zero, negatives and ordinary values mixed
+fn adversarial_amount() -> impl Strategy<Value = i128> {
+    prop_oneof![
+        Just(i128::MIN),
+        Just(-1i128),
+        Just(0i128),
+        Just(1i128),
+        Just(i128::MAX),
+        any::<i128>(),
+        1i128..1_000_000_000,
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn prop_balance_matches_accepted_donations(amounts in prop::collection::vec(adversarial_amount(), 1..8)) {
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        let contract_id = env.register(ZakatContract, ());
+        let client = ZakatContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let donor = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(&admin);
+
+        let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+        let mut expected = 0i128;
+        let mut accepted = 0u32;
+        for amount in amounts {
+            let result = client.try_donate(&id, &donor, &amount);
+            if amount <= 0 {
+                prop_assert_eq!(result, Err(Ok(Error::InvalidAmount.into())));
+                continue;
+            }
+            match expected.checked_add(amount) {
+                Some(sum) => {
+                    prop_assert!(result.is_ok());
+                    expected = sum;
+                    accepted += 1;
+                }
+                None => prop_assert_eq!(result, Err(Ok(Error::Overflow.into()))),
+            }
+        }
+
+        prop_assert!(expected >= 0);
+        prop_assert_eq!(client.get_campaign(&id).current_amount, expected);
+        prop_assert_eq!(client.get_total_donations(), expected);
+        prop_assert_eq!(client.get_donations(&id).len(), accepted);
+    }
 }
 
 #[test]
-#[should_panic(expected = "Campaign is not active")]
-fn test_donate_to_closed_campaign() {
+fn test_verify_integrity() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -425,29 +692,240 @@ fn test_donate_to_closed_campaign() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.donate(&id, &donor, &50_000_000);
+    client.withdraw(&id, &recipient);
+
+    // Completed campaigns still take donations after a withdrawal
+    client.donate(&id, &donor, &5_000_000);
+
+    let report = client.verify_integrity(&id);
+    assert_eq!(report.donation_total, 55_000_000);
+    assert_eq!(report.current_amount, 5_000_000);
+    assert_eq!(report.withdrawn_amount, 50_000_000);
+    assert!(report.is_consistent);
+}
+
+// Randomized operation sequences, checking integrity after every step
+#[derive(Clone, Debug)]
+enum Op {
+    Create(i128),
+    Submit(u32),
+    Approve(u32),
+    Donate(u32, i128),
+    Close(u32),
+    Withdraw(u32),
+    SetPolicy(u32, u8, u32),
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        1 => (1i128..200_000_000).prop_map(Op::Create),
+        1 => (0u32..4).prop_map(Op::Submit),
+        1 => (0u32..4).prop_map(Op::Approve),
+        4 => (0u32..4, adversarial_amount()).prop_map(|(c, a)| Op::Donate(c, a)),
+        1 => (0u32..4).prop_map(Op::Close),
+        1 => (0u32..4).prop_map(Op::Withdraw),
+        1 => (0u32..4, 0u8..4, 0u32..4).prop_map(|(c, p, t)| Op::SetPolicy(c, p, t)),
+    ]
+}
+
+// Excess policy picked by index, redirecting to another existing campaign
+fn excess_policy(policy: u8, target: u32) -> ExcessPolicy {
+    match policy {
+        0 => ExcessPolicy::AcceptAll,
+        1 => ExcessPolicy::RejectExcess,
+        2 => ExcessPolicy::RefundExcess,
+        _ => ExcessPolicy::RedirectExcess(target),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn prop_integrity_holds_across_operations(ops in prop::collection::vec(op_strategy(), 1..20)) {
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
+        let contract_id = env.register(ZakatContract, ());
+        let client = ZakatContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let recipient = Address::generate(&env);
+        let donor = Address::generate(&env);
+
+        env.mock_all_auths();
+        client.initialize(&admin);
+
+        create_active_campaign(&env, &client, &admin, &recipient, 100_000_000);
+
+        let title = String::from_str(&env, "Campaign");
+        let description = String::from_str(&env, "Description");
+
+        for op in ops {
+            // Campaign indices wrap onto the campaigns that exist so far
+            let count = client.get_campaigns().len();
+            let id = |c: u32| c % count + 1;
+
+            // Failed calls roll back, so only the state after each call matters
+            let _ = match op {
+                Op::Create(target) => client
+                    .try_create_campaign(&title, &description, &CampaignCategory::Zakat, &target, &recipient)
+                    .map(|_| ()),
+                Op::Submit(c) => client.try_submit_campaign(&id(c), &recipient).map(|_| ()),
+                Op::Approve(c) => client.try_approve_campaign(&id(c), &admin).map(|_| ()),
+                Op::Donate(c, amount) => client.try_donate(&id(c), &donor, &amount).map(|_| ()),
+                Op::Close(c) => client.try_close_campaign(&id(c), &recipient).map(|_| ()),
+                Op::Withdraw(c) => client.try_withdraw(&id(c), &recipient).map(|_| ()),
+                Op::SetPolicy(c, policy, t) => client
+                    .try_set_excess_policy(&id(c), &recipient, &excess_policy(policy, id(t)))
+                    .map(|_| ()),
+            };
+
+            for campaign in client.get_campaigns().iter() {
+                let report = client.verify_integrity(&campaign.id);
+                prop_assert!(report.is_consistent, "{:?}", report);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_excess_policy_refund_and_redirect() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let general_fund = create_active_campaign(&env, &client, &admin, &recipient, 500_000_000);
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+
+    // Refund: completed campaigns stop growing past the target
+    client.set_excess_policy(&id, &recipient, &ExcessPolicy::RefundExcess);
+    assert_eq!(client.donate(&id, &donor, &60_000_000), 10_000_000);
+    assert_eq!(client.donate(&id, &donor, &5_000_000), 5_000_000);
+    assert_eq!(client.get_campaign(&id).current_amount, 50_000_000);
+    assert_eq!(client.get_donations(&id).len(), 1);
+
+    // Withdrawn funds still count towards the target
+    client.withdraw(&id, &recipient);
+    assert_eq!(client.donate(&id, &donor, &5_000_000), 5_000_000);
+
+    // Redirect: the overflow lands in the general fund
+    client.set_excess_policy(&id, &recipient, &ExcessPolicy::RedirectExcess(general_fund));
+    assert_eq!(client.donate(&id, &donor, &5_000_000), 0);
+    assert_eq!(client.get_campaign(&general_fund).current_amount, 5_000_000);
+    assert!(client.verify_integrity(&id).is_consistent);
+    assert!(client.verify_integrity(&general_fund).is_consistent);
+}
+
+#[test]
+fn test_excess_redirect_single_hop() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let first = create_active_campaign(&env, &client, &admin, &recipient, 10_000_000);
+    let second = create_active_campaign(&env, &client, &admin, &recipient, 10_000_000);
+    client.set_excess_policy(&first, &recipient, &ExcessPolicy::RedirectExcess(second));
+    client.set_excess_policy(&second, &recipient, &ExcessPolicy::RedirectExcess(first));
+
+    // Redirected excess is not forwarded again, the rest is refunded
+    assert_eq!(client.donate(&first, &donor, &25_000_000), 5_000_000);
+    assert_eq!(client.get_campaign(&first).current_amount, 10_000_000);
+    assert_eq!(client.get_campaign(&second).current_amount, 10_000_000);
+}
+
+#[test]
+fn test_excess_redirect_to_closed_target_refunds() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let general_fund = create_active_campaign(&env, &client, &admin, &recipient, 500_000_000);
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_excess_policy(&id, &recipient, &ExcessPolicy::RedirectExcess(general_fund));
+    client.close_campaign(&general_fund, &recipient);
+
+    // The closed general fund cannot take the overflow, so it is refunded
+    assert_eq!(client.donate(&id, &donor, &60_000_000), 10_000_000);
+    assert_eq!(client.get_campaign(&id).current_amount, 50_000_000);
+    assert_eq!(client.get_campaign(&general_fund).current_amount, 0);
+}
+
+#[test]
+fn test_excess_redirect_to_draft_target_refunds() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+
+    let draft = client.create_campaign(
+        &String::from_str(&env, "Dana Umum"),
+        &String::from_str(&env, "Belum disetujui"),
         &CampaignCategory::Zakat,
-        &50_000_000,
+        &500_000_000,
         &recipient,
     );
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_excess_policy(&id, &recipient, &ExcessPolicy::RedirectExcess(draft));
 
-    // Close campaign
-    client.close_campaign(&admin, &campaign_id);
+    // Unapproved campaigns do not receive redirected funds
+    assert_eq!(client.donate(&id, &donor, &60_000_000), 10_000_000);
+    assert_eq!(client.get_campaign(&draft).current_amount, 0);
+    assert_eq!(client.get_donations(&draft).len(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Only recipient can set excess policy")]
+fn test_set_excess_policy_wrong_caller() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
 
-    // Try to donate to closed campaign (should panic)
-    client.donate(&donor, &campaign_id, &10_000_000, &false);
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_excess_policy(&id, &admin, &ExcessPolicy::RejectExcess);
 }
 
 #[test]
-fn test_withdraw_completed_campaign() {
+fn test_flexible_goal_withdraw_while_active() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
@@ -455,139 +933,272 @@ fn test_withdraw_completed_campaign() {
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Withdrawal Test"),
-        &String::from_str(&env, "Test withdrawal"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+    let id = create_active_campaign(&env, &client, &admin, &recipient, 50_000_000);
+    client.set_goal_type(&id, &recipient, &GoalType::Flexible);
+
+    client.donate(&id, &donor, &60_000_000);
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Active);
+    assert_eq!(client.withdraw(&id, &recipient), 60_000_000);
+
+    // Open-ended campaigns drop their target entirely
+    client.set_goal_type(&id, &recipient, &GoalType::OpenEnded);
+    client.donate(&id, &donor, &5_000_000);
+
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.target_amount, 0);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(client.withdraw(&id, &recipient), 5_000_000);
+}
+
+#[test]
+fn test_emergency_campaign_skips_review() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let responder = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.add_responder(&admin, &responder);
+    client.set_emergency_duration(&admin, &1_000);
+
+    let lombok = String::from_str(&env, "ID-NB");
+    env.ledger().with_mut(|l| l.timestamp = 5_000);
+    let id = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &100_000_000,
         &recipient,
+        &lombok,
     );
 
-    // Donate to complete campaign
-    client.donate(&donor, &campaign_id, &50_000_000, &false);
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.status, CampaignStatus::Active);
+    assert_eq!(campaign.category, CampaignCategory::BencanaAlam);
+    assert_eq!(client.get_emergency_expiry(&id), Some(6_000));
 
-    // Withdraw
-    let withdrawn_amount = client.withdraw(&recipient, &campaign_id);
-    assert_eq!(withdrawn_amount, 50_000_000);
+    client.donate(&id, &donor, &10_000_000);
 
-    // Check campaign is closed and amount is 0
-    let campaign = client.get_campaign(&campaign_id);
-    assert_eq!(campaign.current_amount, 0);
-    assert_eq!(campaign.status, CampaignStatus::Closed);
+    // After the cap the campaign needs a review before new donations
+    env.ledger().with_mut(|l| l.timestamp = 6_001);
+    assert!(client.try_donate(&id, &donor, &10_000_000).is_err());
+    client.confirm_emergency_campaign(&id, &admin);
+    client.donate(&id, &donor, &10_000_000);
+    assert_eq!(client.get_emergency_expiry(&id), None);
+    assert_eq!(client.get_campaign(&id).current_amount, 20_000_000);
 }
 
 #[test]
-#[should_panic(expected = "Campaign must be completed to withdraw")]
-fn test_withdraw_active_campaign() {
+#[should_panic(expected = "Only responder can create emergency campaigns")]
+fn test_emergency_campaign_not_responder() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
     let recipient = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+    client.create_emergency_campaign(
         &recipient,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &100_000_000,
+        &recipient,
+        &String::from_str(&env, "ID-NB"),
     );
-
-    // Try to withdraw from active campaign (should panic)
-    client.withdraw(&recipient, &campaign_id);
 }
 
 #[test]
-#[should_panic(expected = "Only recipient can withdraw")]
-fn test_withdraw_wrong_recipient() {
+fn test_region_views() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    let responder = Address::generate(&env);
     let recipient = Address::generate(&env);
-    let wrong_recipient = Address::generate(&env);
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
-
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
+    client.add_responder(&admin, &responder);
+
+    let lombok = String::from_str(&env, "ID-NB");
+    let palu = String::from_str(&env, "ID-ST");
+    let quake = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &10_000_000,
+        &recipient,
+        &lombok,
+    );
+    let shelter = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Hunian Sementara"),
+        &String::from_str(&env, "Tenda untuk pengungsi"),
         &50_000_000,
         &recipient,
+        &lombok,
+    );
+    let regular = client.create_campaign(
+        &String::from_str(&env, "Banjir Palu"),
+        &String::from_str(&env, "Donasi untuk korban banjir"),
+        &CampaignCategory::BencanaAlam,
+        &50_000_000,
+        &recipient,
+    );
+    client.set_region(&regular, &recipient, &palu);
+    client.submit_campaign(&regular, &recipient);
+    client.approve_campaign(&regular, &admin);
+
+    // The region is fixed once the campaign is under review
+    assert!(client.try_set_region(&regular, &recipient, &lombok).is_err());
+
+    // A completed campaign still counts towards the region total
+    client.donate(&quake, &donor, &10_000_000);
+    client.donate(&shelter, &donor, &5_000_000);
+    client.donate(&regular, &donor, &7_000_000);
+
+    let active = client.get_active_campaigns_by_region(&lombok);
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().id, shelter);
+
+    let totals = client.get_region_totals();
+    assert_eq!(totals.len(), 2);
+    for entry in totals.iter() {
+        if entry.region == lombok {
+            assert_eq!(entry.total_raised, 15_000_000);
+            assert_eq!(entry.active_campaigns, 1);
+        } else {
+            assert_eq!(entry.region, palu);
+            assert_eq!(entry.total_raised, 7_000_000);
+            assert_eq!(entry.active_campaigns, 1);
+        }
+    }
+}
+
+#[test]
+fn test_expired_emergency_campaign_review() {
+    let env = Env::default();
+    let contract_id = env.register(ZakatContract, ());
+    let client = ZakatContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let responder = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
+
+    env.mock_all_auths();
+    client.initialize(&admin);
+    client.add_responder(&admin, &responder);
+    client.set_emergency_duration(&admin, &1_000);
+
+    let lombok = String::from_str(&env, "ID-NB");
+    let id = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &100_000_000,
+        &recipient,
+        &lombok,
     );
+    client.donate(&id, &donor, &10_000_000);
+
+    // Funds stay locked until a reviewer confirms the campaign
+    assert!(client.try_withdraw(&id, &recipient).is_err());
 
-    // Complete campaign
-    client.donate(&donor, &campaign_id, &50_000_000, &false);
+    env.ledger().with_mut(|l| l.timestamp = 1_001);
+    assert_eq!(client.get_active_campaigns_by_region(&lombok).len(), 0);
 
-    // Try to withdraw with wrong recipient (should panic)
-    client.withdraw(&wrong_recipient, &campaign_id);
+    client.reject_campaign(&id, &admin, &String::from_str(&env, "Penerima tidak valid"));
+    assert_eq!(client.get_campaign(&id).status, CampaignStatus::Closed);
+    assert!(client.get_rejection(&id).is_some());
+    assert!(client.try_withdraw(&id, &recipient).is_err());
+    assert!(client.try_confirm_emergency_campaign(&id, &admin).is_err());
+
+    // A rejected campaign's balance is refunded instead
+    assert_eq!(client.refund_emergency_campaign(&id, &admin), 10_000_000);
+    assert_eq!(client.get_emergency_expiry(&id), None);
+    let campaign = client.get_campaign(&id);
+    assert_eq!(campaign.current_amount, 0);
+    assert_eq!(campaign.refunded_amount, 10_000_000);
+    assert!(client.verify_integrity(&id).is_consistent);
+    assert_eq!(client.withdraw(&id, &recipient), 0);
 }
 
 #[test]
-#[should_panic(expected = "Amount must be greater than 0")]
-fn test_donate_zero_amount() {
+fn test_closed_emergency_campaign_confirm() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    let responder = Address::generate(&env);
     let recipient = Address::generate(&env);
     let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
+    client.add_responder(&admin, &responder);
 
-    let campaign_id = client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &50_000_000,
+    let id = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &100_000_000,
         &recipient,
+        &String::from_str(&env, "ID-NB"),
     );
+    client.donate(&id, &donor, &10_000_000);
+
+    // Open campaigns are confirmed, not refunded
+    assert!(client.try_refund_emergency_campaign(&id, &admin).is_err());
 
-    // Try to donate 0 amount (should panic)
-    client.donate(&donor, &campaign_id, &0, &false);
+    // Closed before review, the funds wait for confirmation
+    client.close_campaign(&id, &recipient);
+    assert!(client.try_withdraw(&id, &recipient).is_err());
+
+    client.confirm_emergency_campaign(&id, &admin);
+    assert_eq!(client.withdraw(&id, &recipient), 10_000_000);
+    assert!(client.try_refund_emergency_campaign(&id, &admin).is_err());
 }
 
 #[test]
-#[should_panic(expected = "Target amount must be greater than 0")]
-fn test_create_campaign_zero_target() {
+fn test_confirmed_emergency_campaign_withdraw() {
     let env = Env::default();
-    let contract_id = env.register_contract(None, ZakatContract);
+    let contract_id = env.register(ZakatContract, ());
     let client = ZakatContractClient::new(&env, &contract_id);
 
     let admin = Address::generate(&env);
+    let responder = Address::generate(&env);
     let recipient = Address::generate(&env);
+    let donor = Address::generate(&env);
 
     env.mock_all_auths();
-    
     client.initialize(&admin);
+    client.add_responder(&admin, &responder);
 
-    // Try to create campaign with 0 target (should panic)
-    client.create_campaign(
-        &admin,
-        &String::from_str(&env, "Test Campaign"),
-        &String::from_str(&env, "Description"),
-        &CampaignCategory::Zakat,
-        &0,
+    let id = client.create_emergency_campaign(
+        &responder,
+        &String::from_str(&env, "Gempa Lombok"),
+        &String::from_str(&env, "Tanggap darurat gempa"),
+        &10_000_000,
         &recipient,
+        &String::from_str(&env, "ID-NB"),
     );
-}
\ No newline at end of file
+    client.donate(&id, &donor, &10_000_000);
+
+    client.confirm_emergency_campaign(&id, &admin);
+    assert_eq!(client.withdraw(&id, &recipient), 10_000_000);
+}